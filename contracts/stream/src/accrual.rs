@@ -1,20 +1,25 @@
 /// Computes accrued stream amount without relying on Soroban environment state.
 ///
 /// This helper is intentionally pure to make the core vesting math easy to unit test.
+/// It is the closed-form schedule that `accrue_from_checkpoint` must reproduce.
 ///
 /// Rules:
 /// - Returns `0` before `cliff_time`.
 /// - Returns `0` for invalid schedules (`start_time >= end_time`) or negative rates.
+/// - With `cliff_time == end_time` there is no linear window: the stream is a single
+///   unlock of the whole `deposit_amount` at `end_time`.
 /// - Uses `min(current_time, end_time)` so accrual is capped at stream end.
-/// - Multiplies elapsed seconds by `rate_per_second`, and on multiplication overflow
-///   returns `deposit_amount` (safe upper bound before final clamping).
+/// - Multiplies elapsed seconds by `rate_per_second` and adds `cliff_unlock_amount`;
+///   on overflow returns `deposit_amount` (safe upper bound before final clamping).
 /// - Final result is clamped to `[0, deposit_amount]`.
+#[cfg(test)]
 pub fn calculate_accrued_amount(
     start_time: u64,
     cliff_time: u64,
     end_time: u64,
     rate_per_second: i128,
     deposit_amount: i128,
+    cliff_unlock_amount: i128,
     current_time: u64,
 ) -> i128 {
     if current_time < cliff_time {
@@ -25,13 +30,20 @@ pub fn calculate_accrued_amount(
         return 0;
     }
 
+    if cliff_time >= end_time {
+        return deposit_amount.max(0);
+    }
+
     let elapsed_now = current_time.min(end_time);
     let elapsed_seconds = match elapsed_now.checked_sub(start_time) {
         Some(elapsed) => elapsed as i128,
         None => return 0,
     };
 
-    let accrued = match elapsed_seconds.checked_mul(rate_per_second) {
+    let accrued = match elapsed_seconds
+        .checked_mul(rate_per_second)
+        .and_then(|linear| linear.checked_add(cliff_unlock_amount))
+    {
         Some(amount) => amount,
         None => deposit_amount,
     };
@@ -39,45 +51,375 @@ pub fn calculate_accrued_amount(
     accrued.min(deposit_amount).max(0)
 }
 
+/// Computes accrued stream amount from the last accrual checkpoint.
+///
+/// `checkpoint` is the amount accrued at `checkpoint_time`; a fresh stream has
+/// `checkpoint = cliff_unlock_amount` and `checkpoint_time = start_time`, which makes
+/// this identical to `calculate_accrued_amount`. Checkpoints are only taken at or after the cliff,
+/// so the cliff rule still applies unchanged.
+///
+/// Rules:
+/// - Returns `0` before `cliff_time` or for negative rates.
+/// - Returns `deposit_amount` from `end_time` on when `cliff_time == end_time`, matching
+///   `calculate_accrued_amount`.
+/// - Adds `(min(current_time, end_time) - checkpoint_time) * rate_per_second` to the
+///   checkpoint; a time at or before the checkpoint adds nothing.
+/// - On multiplication or addition overflow returns `deposit_amount`.
+/// - Final result is clamped to `[0, deposit_amount]`.
+pub fn accrue_from_checkpoint(
+    checkpoint: i128,
+    checkpoint_time: u64,
+    cliff_time: u64,
+    end_time: u64,
+    rate_per_second: i128,
+    deposit_amount: i128,
+    current_time: u64,
+) -> i128 {
+    if current_time < cliff_time || rate_per_second < 0 {
+        return 0;
+    }
+
+    // No linear window: everything unlocks together at the end, including any deposit
+    // above `rate * duration` that a linear schedule would never reach.
+    if cliff_time >= end_time {
+        return deposit_amount.max(0);
+    }
+
+    let elapsed_seconds = current_time.min(end_time).saturating_sub(checkpoint_time) as i128;
+
+    let accrued = match elapsed_seconds
+        .checked_mul(rate_per_second)
+        .and_then(|delta| checkpoint.checked_add(delta))
+    {
+        Some(amount) => amount,
+        None => deposit_amount,
+    };
+
+    accrued.min(deposit_amount).max(0)
+}
+
+/// Seconds in one hour, the granularity of a weekly schedule mask.
+const SECONDS_PER_HOUR: u64 = 3_600;
+
+/// Seconds in one week, the period of a weekly schedule mask.
+const SECONDS_PER_WEEK: u64 = 7 * 24 * SECONDS_PER_HOUR;
+
+/// Whether the UTC hour starting at `hour * 3600` is enabled by a weekly schedule mask.
+///
+/// Bit `d` of `days` enables weekday `d` (`0` = Monday through `6` = Sunday) and bit
+/// `h` of `hours` enables the hour `h:00`-`h:59` on every enabled day.
+fn is_active_hour(hour: u64, days: u32, hours: u32) -> bool {
+    // 1970-01-01, hour 0 of the epoch, was a Thursday.
+    let weekday = (hour / 24 + 3) % 7;
+    (days & (1 << weekday)) != 0 && (hours & (1 << (hour % 24))) != 0
+}
+
+/// Counts the seconds in `[from, to)` that fall in active hours of a weekly schedule
+/// mask (see `is_active_hour`); bits above the week's 7 days or the day's 24 hours are
+/// ignored.
+///
+/// Whole weeks are counted in closed form and at most one week of hours is walked, so
+/// the cost is bounded and the result never exceeds `to - from`. Returns `0` when
+/// `from >= to`.
+pub fn active_seconds_between(from: u64, to: u64, days: u32, hours: u32) -> u64 {
+    if from >= to {
+        return 0;
+    }
+
+    let active_hours_per_week = (days & 0x7f).count_ones() * (hours & 0xff_ffff).count_ones();
+    let weeks = (to - from) / SECONDS_PER_WEEK;
+    let mut active = weeks * active_hours_per_week as u64 * SECONDS_PER_HOUR;
+
+    let mut time = from + weeks * SECONDS_PER_WEEK;
+    while time < to {
+        let hour = time / SECONDS_PER_HOUR;
+        let hour_end = (hour + 1).saturating_mul(SECONDS_PER_HOUR).min(to);
+        if is_active_hour(hour, days, hours) {
+            active += hour_end - time;
+        }
+        time = hour_end;
+    }
+    active
+}
+
+/// Computes accrued amount for a linear stream that only accrues during the active
+/// hours of a weekly schedule mask.
+///
+/// Same rules as `accrue_from_checkpoint`, except that elapsed time is the number of
+/// active seconds (`active_seconds_between`) from `checkpoint_time` to
+/// `min(current_time, end_time)` rather than wall-clock seconds.
+#[allow(clippy::too_many_arguments)]
+pub fn accrue_scheduled_from_checkpoint(
+    checkpoint: i128,
+    checkpoint_time: u64,
+    cliff_time: u64,
+    end_time: u64,
+    rate_per_second: i128,
+    deposit_amount: i128,
+    days: u32,
+    hours: u32,
+    current_time: u64,
+) -> i128 {
+    if current_time < cliff_time || rate_per_second < 0 {
+        return 0;
+    }
+
+    if cliff_time >= end_time {
+        return deposit_amount.max(0);
+    }
+
+    let elapsed_seconds =
+        active_seconds_between(checkpoint_time, current_time.min(end_time), days, hours) as i128;
+
+    let accrued = match elapsed_seconds
+        .checked_mul(rate_per_second)
+        .and_then(|delta| checkpoint.checked_add(delta))
+    {
+        Some(amount) => amount,
+        None => deposit_amount,
+    };
+
+    accrued.min(deposit_amount).max(0)
+}
+
+/// Fixed-point scale of the front-loaded release curve (`1.0 == CURVE_SCALE`).
+const CURVE_SCALE: u128 = 1_000_000_000_000_000_000;
+
+/// Share of the front-loaded curve released `elapsed` seconds in, in units of
+/// `CURVE_SCALE * halflife` (the whole curve).
+///
+/// The unreleased share halves at every whole half-life and is interpolated linearly
+/// in between, so the release rate halves every `halflife` seconds. Keeping the
+/// `halflife` factor instead of dividing by it makes the value exact in integers; it
+/// stays below `2^124`.
+fn front_loaded_released(elapsed: u64, halflife: u64) -> u128 {
+    let whole = CURVE_SCALE * halflife as u128;
+    let halvings = elapsed / halflife;
+    if halvings >= 64 {
+        return whole;
+    }
+    let level = CURVE_SCALE >> halvings;
+    let into_period = (elapsed % halflife) as u128;
+    whole - (level * halflife as u128 - (level >> 1) * into_period)
+}
+
+/// Computes `amount * numerator / denominator` rounding down, for
+/// `numerator <= denominator` and a nonzero `denominator`, without overflowing.
+///
+/// Both ratio terms are shifted below `2^63` first, so the result is exact for
+/// ratios of that size and within `2^-63` relative error otherwise.
+fn scale_down(amount: u128, numerator: u128, denominator: u128) -> u128 {
+    let shift = (128 - denominator.leading_zeros()).saturating_sub(63);
+    let (numerator, denominator) = (numerator >> shift, denominator >> shift);
+    let whole = amount / denominator;
+    let rest = amount % denominator;
+    whole * numerator + rest * numerator / denominator
+}
+
+/// Computes accrued amount for a front-loaded (decreasing-rate) stream.
+///
+/// The release rate halves every `halflife_seconds`, so more is released early than
+/// on a linear schedule. The curve is rescaled so that exactly `deposit_amount` has
+/// accrued at `end_time`. `cliff_unlock_amount` is released at the cliff and the curve
+/// then applies to the rest of the deposit.
+///
+/// Rules:
+/// - Returns `0` before `cliff_time`, for invalid schedules (`start_time >= end_time`),
+///   or for `halflife_seconds == 0`.
+/// - Returns `deposit_amount` from `end_time` on.
+/// - Otherwise `cliff_unlock_amount + (deposit_amount - cliff_unlock_amount) *
+///   released(elapsed) / released(duration)`, where elapsed time counts from
+///   `start_time` like the linear schedule.
+/// - Final result is clamped to `[0, deposit_amount]`.
+#[allow(clippy::too_many_arguments)]
+pub fn calculate_front_loaded_amount(
+    start_time: u64,
+    cliff_time: u64,
+    end_time: u64,
+    halflife_seconds: u64,
+    cliff_unlock_amount: i128,
+    deposit_amount: i128,
+    current_time: u64,
+) -> i128 {
+    if current_time < cliff_time || start_time >= end_time || halflife_seconds == 0 {
+        return 0;
+    }
+    if current_time >= end_time {
+        return deposit_amount.max(0);
+    }
+
+    let duration = end_time - start_time;
+    let elapsed = current_time.saturating_sub(start_time);
+    let released = front_loaded_released(elapsed, halflife_seconds);
+    let released_total = front_loaded_released(duration, halflife_seconds);
+
+    let curve_amount = (deposit_amount - cliff_unlock_amount).max(0) as u128;
+    let curve_accrued = scale_down(curve_amount, released, released_total);
+
+    cliff_unlock_amount
+        .saturating_add(curve_accrued as i128)
+        .min(deposit_amount)
+        .max(0)
+}
+
+/// Computes accrued amount for a single-unlock (time-lock) stream.
+///
+/// Returns `0` before `cliff_time` and the whole `deposit_amount` (clamped at `0`)
+/// from `cliff_time` on.
+pub fn calculate_single_unlock_amount(
+    cliff_time: u64,
+    deposit_amount: i128,
+    current_time: u64,
+) -> i128 {
+    if current_time < cliff_time {
+        return 0;
+    }
+    deposit_amount.max(0)
+}
+
 #[cfg(test)]
 mod tests {
-    use super::calculate_accrued_amount;
+    use super::{
+        accrue_from_checkpoint, calculate_accrued_amount, calculate_front_loaded_amount,
+        calculate_single_unlock_amount,
+    };
 
     #[test]
     fn returns_zero_before_cliff() {
-        let accrued = calculate_accrued_amount(0, 500, 1000, 1, 1000, 499);
+        let accrued = calculate_accrued_amount(0, 500, 1000, 1, 1000, 0, 499);
         assert_eq!(accrued, 0);
     }
 
     #[test]
     fn accrues_from_start_at_cliff() {
-        let accrued = calculate_accrued_amount(0, 500, 1000, 1, 1000, 500);
+        let accrued = calculate_accrued_amount(0, 500, 1000, 1, 1000, 0, 500);
         assert_eq!(accrued, 500);
     }
 
+    #[test]
+    fn cliff_at_end_unlocks_whole_deposit_at_end() {
+        assert_eq!(calculate_accrued_amount(0, 1000, 1000, 1, 1000, 0, 999), 0);
+        assert_eq!(
+            calculate_accrued_amount(0, 1000, 1000, 1, 1000, 0, 1000),
+            1000
+        );
+        // Over-funded: the excess a linear schedule never reaches unlocks too.
+        assert_eq!(
+            calculate_accrued_amount(0, 1000, 1000, 1, 1500, 0, 1000),
+            1500
+        );
+        assert_eq!(accrue_from_checkpoint(0, 0, 1000, 1000, 1, 1500, 999), 0);
+        assert_eq!(
+            accrue_from_checkpoint(0, 0, 1000, 1000, 1, 1500, 1000),
+            1500
+        );
+    }
+
     #[test]
     fn caps_at_end_time_and_deposit() {
-        let accrued = calculate_accrued_amount(0, 0, 1000, 2, 1000, 9_999);
+        let accrued = calculate_accrued_amount(0, 0, 1000, 2, 1000, 0, 9_999);
         assert_eq!(accrued, 1000);
     }
 
     #[test]
     fn returns_zero_for_invalid_schedule() {
-        let accrued = calculate_accrued_amount(10, 10, 10, 1, 1000, 10);
+        let accrued = calculate_accrued_amount(10, 10, 10, 1, 1000, 0, 10);
         assert_eq!(accrued, 0);
     }
 
     #[test]
     fn returns_zero_for_negative_rate() {
-        let accrued = calculate_accrued_amount(0, 0, 1000, -1, 1000, 100);
+        let accrued = calculate_accrued_amount(0, 0, 1000, -1, 1000, 0, 100);
         assert_eq!(accrued, 0);
     }
 
     #[test]
     fn multiplication_overflow_returns_capped_deposit() {
-        let accrued = calculate_accrued_amount(0, 0, u64::MAX, i128::MAX, 10_000, u64::MAX);
+        let accrued = calculate_accrued_amount(0, 0, u64::MAX, i128::MAX, 10_000, 0, u64::MAX);
         assert_eq!(accrued, 10_000);
     }
+
+    #[test]
+    fn front_loaded_beats_linear_at_quarter_time() {
+        let front = calculate_front_loaded_amount(0, 0, 1000, 250, 0, 1000, 250);
+        let linear = calculate_accrued_amount(0, 0, 1000, 1, 1000, 0, 250);
+        assert_eq!(linear, 250);
+        // Half of the curve released after one half-life, rescaled by 1 - 1/16.
+        assert_eq!(front, 533);
+        assert!(front > linear);
+    }
+
+    #[test]
+    fn front_loaded_converges_to_deposit_at_end() {
+        assert_eq!(
+            calculate_front_loaded_amount(0, 0, 1000, 250, 0, 1000, 999),
+            999
+        );
+        assert_eq!(
+            calculate_front_loaded_amount(0, 0, 1000, 250, 0, 1000, 1000),
+            1000
+        );
+        assert_eq!(
+            calculate_front_loaded_amount(0, 0, 1000, 250, 0, 1000, u64::MAX),
+            1000
+        );
+    }
+
+    #[test]
+    fn front_loaded_is_monotonic_and_respects_cliff() {
+        let mut prev = 0;
+        for t in 0..=1000 {
+            let accrued = calculate_front_loaded_amount(0, 100, 1000, 37, 50, 1000, t);
+            if t < 100 {
+                assert_eq!(accrued, 0);
+            } else {
+                assert!(accrued >= prev && accrued >= 50);
+            }
+            prev = accrued;
+        }
+        assert_eq!(prev, 1000);
+    }
+
+    #[test]
+    fn front_loaded_huge_values_do_not_overflow() {
+        let deposit = i128::MAX;
+        let mid = calculate_front_loaded_amount(0, 0, u64::MAX, 1, 0, deposit, u64::MAX / 2);
+        assert!(mid > 0 && mid <= deposit);
+        let flat = calculate_front_loaded_amount(0, 0, 1000, u64::MAX, 0, deposit, 500);
+        assert_eq!(flat, deposit / 2);
+    }
+
+    #[test]
+    fn cliff_unlock_hidden_before_cliff() {
+        let accrued = calculate_accrued_amount(0, 500, 1000, 1, 1250, 250, 499);
+        assert_eq!(accrued, 0);
+    }
+
+    #[test]
+    fn cliff_unlock_released_at_cliff() {
+        let accrued = calculate_accrued_amount(0, 500, 1000, 1, 1250, 250, 500);
+        assert_eq!(accrued, 750);
+    }
+
+    #[test]
+    fn cliff_unlock_plus_linear_caps_at_deposit() {
+        assert_eq!(
+            calculate_accrued_amount(0, 500, 1000, 1, 1250, 250, 800),
+            1050
+        );
+        assert_eq!(
+            calculate_accrued_amount(0, 500, 1000, 1, 1000, 250, 800),
+            1000
+        );
+    }
+
+    #[test]
+    fn single_unlock_releases_everything_at_cliff() {
+        assert_eq!(calculate_single_unlock_amount(500, 1000, 0), 0);
+        assert_eq!(calculate_single_unlock_amount(500, 1000, 499), 0);
+        assert_eq!(calculate_single_unlock_amount(500, 1000, 500), 1000);
+        assert_eq!(calculate_single_unlock_amount(500, 1000, u64::MAX), 1000);
+    }
 }
 
 #[cfg(test)]
@@ -110,7 +452,7 @@ mod invariants {
             ];
 
             for &t in &times {
-                let accrued = calculate_accrued_amount(start, cliff, end, rate, deposit, t);
+                let accrued = calculate_accrued_amount(start, cliff, end, rate, deposit, 0, t);
 
                 assert!(
                     accrued >= 0,
@@ -156,10 +498,11 @@ mod invariants {
             times_buf[len] = end;
             len += 1;
 
-            let mut prev = calculate_accrued_amount(start, cliff, end, rate, deposit, times_buf[0]);
+            let mut prev =
+                calculate_accrued_amount(start, cliff, end, rate, deposit, 0, times_buf[0]);
 
             for &t in times_buf.iter().take(len).skip(1) {
-                let now = calculate_accrued_amount(start, cliff, end, rate, deposit, t);
+                let now = calculate_accrued_amount(start, cliff, end, rate, deposit, 0, t);
 
                 assert!(
                     now >= prev,
@@ -213,7 +556,7 @@ mod accrued_after_end_time {
     #[test]
     fn exactly_at_end_time_equals_deposit() {
         let (start, cliff, end, rate, deposit) = standard_stream();
-        let accrued = calculate_accrued_amount(start, cliff, end, rate, deposit, end);
+        let accrued = calculate_accrued_amount(start, cliff, end, rate, deposit, 0, end);
         assert_eq!(
             accrued, deposit,
             "at end_time, accrued should equal deposit_amount"
@@ -224,7 +567,7 @@ mod accrued_after_end_time {
     #[test]
     fn one_second_after_end_time_still_capped() {
         let (start, cliff, end, rate, deposit) = standard_stream();
-        let accrued = calculate_accrued_amount(start, cliff, end, rate, deposit, end + 1);
+        let accrued = calculate_accrued_amount(start, cliff, end, rate, deposit, 0, end + 1);
         assert_eq!(
             accrued, deposit,
             "one second past end_time should not accrue more than deposit_amount"
@@ -236,7 +579,7 @@ mod accrued_after_end_time {
     fn long_after_end_time_still_capped() {
         let (start, cliff, end, rate, deposit) = standard_stream();
         let far_future = end + 10_000;
-        let accrued = calculate_accrued_amount(start, cliff, end, rate, deposit, far_future);
+        let accrued = calculate_accrued_amount(start, cliff, end, rate, deposit, 0, far_future);
         assert_eq!(
             accrued, deposit,
             "long after end_time, accrued must be capped at deposit_amount"
@@ -247,7 +590,7 @@ mod accrued_after_end_time {
     #[test]
     fn max_time_does_not_overflow() {
         let (start, cliff, end, rate, deposit) = standard_stream();
-        let accrued = calculate_accrued_amount(start, cliff, end, rate, deposit, u64::MAX);
+        let accrued = calculate_accrued_amount(start, cliff, end, rate, deposit, 0, u64::MAX);
         assert_eq!(
             accrued, deposit,
             "u64::MAX current_time should cap safely at deposit_amount"
@@ -262,7 +605,7 @@ mod accrued_after_end_time {
     #[test]
     fn one_second_before_end_time_less_than_deposit() {
         let (start, cliff, end, rate, deposit) = standard_stream();
-        let accrued = calculate_accrued_amount(start, cliff, end, rate, deposit, end - 1);
+        let accrued = calculate_accrued_amount(start, cliff, end, rate, deposit, 0, end - 1);
         assert!(
             accrued < deposit,
             "one second before end_time, accrued ({accrued}) should be less than deposit ({deposit})"
@@ -274,7 +617,7 @@ mod accrued_after_end_time {
     #[test]
     fn at_start_time_accrues_zero() {
         let (start, cliff, end, rate, deposit) = standard_stream();
-        let accrued = calculate_accrued_amount(start, cliff, end, rate, deposit, start);
+        let accrued = calculate_accrued_amount(start, cliff, end, rate, deposit, 0, start);
         assert_eq!(accrued, 0, "at start_time, nothing should have accrued yet");
     }
 
@@ -283,7 +626,7 @@ mod accrued_after_end_time {
     fn midway_accrues_half_deposit() {
         let (start, cliff, end, rate, deposit) = standard_stream();
         let midpoint = (start + end) / 2; // 1500
-        let accrued = calculate_accrued_amount(start, cliff, end, rate, deposit, midpoint);
+        let accrued = calculate_accrued_amount(start, cliff, end, rate, deposit, 0, midpoint);
         assert_eq!(
             accrued, 500,
             "halfway through, should accrue half the deposit"
@@ -304,6 +647,7 @@ mod accrued_after_end_time {
             1_000, // end
             10,    // rate_per_second
             5_000, // deposit (lower than rate * duration)
+            0,     // cliff_unlock_amount
             1_000, // current_time == end_time
         );
         assert_eq!(
@@ -316,7 +660,7 @@ mod accrued_after_end_time {
     #[test]
     fn high_rate_long_after_end_still_caps_at_deposit() {
         let accrued = calculate_accrued_amount(
-            0, 0, 1_000, 10, 5_000, 999_999, // far future
+            0, 0, 1_000, 10, 5_000, 0, 999_999, // far future
         );
         assert_eq!(accrued, 5_000);
     }
@@ -337,6 +681,7 @@ mod accrued_after_end_time {
             1_000, // end
             1,     // rate
             1_000, // deposit
+            0,     // cliff_unlock_amount
             2_000, // current_time > end but < cliff
         );
         assert_eq!(
@@ -354,8 +699,8 @@ mod accrued_after_end_time {
     fn pure_function_same_result_on_repeat_calls() {
         let (start, cliff, end, rate, deposit) = standard_stream();
         let t = end + 500;
-        let first = calculate_accrued_amount(start, cliff, end, rate, deposit, t);
-        let second = calculate_accrued_amount(start, cliff, end, rate, deposit, t);
+        let first = calculate_accrued_amount(start, cliff, end, rate, deposit, 0, t);
+        let second = calculate_accrued_amount(start, cliff, end, rate, deposit, 0, t);
         assert_eq!(first, second, "pure function must be deterministic");
         assert_eq!(first, deposit);
     }
@@ -378,10 +723,280 @@ mod accrued_after_end_time {
         // so expected = min(3000, 2000) = 2000
         let expected = (rate * (end - start) as i128).min(deposit);
 
-        let accrued = calculate_accrued_amount(start, cliff, end, rate, deposit, end + 9_999);
+        let accrued = calculate_accrued_amount(start, cliff, end, rate, deposit, 0, end + 9_999);
         assert_eq!(
             accrued, expected,
             "result must match the documented cap formula: min(rate*(end-start), deposit)"
         );
     }
 }
+
+/// Checkpointed accrual must match the closed-form schedule no matter where the
+/// checkpoints fall, for streams whose rate never changes.
+#[cfg(test)]
+mod checkpoint_equivalence {
+    use super::{accrue_from_checkpoint, calculate_accrued_amount};
+
+    /// Small deterministic PRNG so the property test is reproducible.
+    struct Lcg(u64);
+
+    impl Lcg {
+        fn next(&mut self) -> u64 {
+            self.0 = self
+                .0
+                .wrapping_mul(6_364_136_223_846_793_005)
+                .wrapping_add(1_442_695_040_888_963_407);
+            self.0 >> 33
+        }
+
+        fn below(&mut self, bound: u64) -> u64 {
+            self.next() % bound
+        }
+    }
+
+    #[test]
+    fn fresh_checkpoint_matches_closed_form() {
+        let mut rng = Lcg(7);
+        for _ in 0..500 {
+            let start = rng.below(10_000);
+            let cliff = start + rng.below(2_000);
+            let end = cliff + 1 + rng.below(10_000);
+            let rate = 1 + rng.below(1_000) as i128;
+            let deposit = rate * (end - start) as i128 + rng.below(5_000) as i128;
+            let now = rng.below(end + 5_000);
+
+            let cliff_unlock = rng.below(deposit as u64 + 1) as i128;
+
+            assert_eq!(
+                accrue_from_checkpoint(0, start, cliff, end, rate, deposit, now),
+                calculate_accrued_amount(start, cliff, end, rate, deposit, 0, now),
+            );
+            assert_eq!(
+                accrue_from_checkpoint(cliff_unlock, start, cliff, end, rate, deposit, now),
+                calculate_accrued_amount(start, cliff, end, rate, deposit, cliff_unlock, now),
+            );
+        }
+    }
+
+    #[test]
+    fn chained_checkpoints_match_closed_form() {
+        let mut rng = Lcg(42);
+        for _ in 0..200 {
+            let start = rng.below(10_000);
+            let cliff = start + rng.below(2_000);
+            let end = cliff + 1 + rng.below(10_000);
+            let rate = 1 + rng.below(1_000) as i128;
+            let deposit = rate * (end - start) as i128;
+
+            let mut checkpoint = 0;
+            let mut checkpoint_time = start;
+            let mut now = 0;
+            for _ in 0..10 {
+                now += rng.below((end + 2_000) / 5 + 1);
+
+                let expected = calculate_accrued_amount(start, cliff, end, rate, deposit, 0, now);
+                let actual = accrue_from_checkpoint(
+                    checkpoint,
+                    checkpoint_time,
+                    cliff,
+                    end,
+                    rate,
+                    deposit,
+                    now,
+                );
+                assert_eq!(
+                    actual,
+                    expected,
+                    "stream {:?} at t={}",
+                    (start, cliff, end),
+                    now
+                );
+
+                // Checkpoint the way the contract does: only from the cliff onwards.
+                if now >= cliff && now > checkpoint_time {
+                    checkpoint = actual;
+                    checkpoint_time = now.min(end);
+                }
+            }
+        }
+    }
+}
+
+/// Schedule-masked accrual: only the active hours of the weekly mask count towards
+/// elapsed time. Timestamp `0` is a Thursday at 00:00 UTC.
+#[cfg(test)]
+mod scheduled_accrual {
+    use super::{accrue_scheduled_from_checkpoint, active_seconds_between, SECONDS_PER_WEEK};
+
+    const HOUR: u64 = 3_600;
+    const DAY: u64 = 24 * HOUR;
+    const ALL_DAYS: u32 = 0x7f;
+    const ALL_HOURS: u32 = 0xff_ffff;
+    /// Hours 0, 2, 4, ..., 22.
+    const EVEN_HOURS: u32 = 0x55_5555;
+
+    #[test]
+    fn even_hours_are_half_of_a_day() {
+        assert_eq!(
+            active_seconds_between(0, DAY, ALL_DAYS, EVEN_HOURS),
+            DAY / 2
+        );
+    }
+
+    #[test]
+    fn partial_hours_count_only_their_active_part() {
+        // Second half of hour 0 (active) and first half of hour 1 (inactive).
+        assert_eq!(
+            active_seconds_between(HOUR / 2, HOUR + HOUR / 2, ALL_DAYS, EVEN_HOURS),
+            HOUR / 2
+        );
+        assert_eq!(
+            active_seconds_between(HOUR, 2 * HOUR, ALL_DAYS, EVEN_HOURS),
+            0
+        );
+        assert_eq!(
+            active_seconds_between(HOUR + 1, 2 * HOUR + 1, ALL_DAYS, EVEN_HOURS),
+            1
+        );
+    }
+
+    #[test]
+    fn weekday_bits_start_on_monday() {
+        let monday_only = 1;
+        // Thursday through Sunday.
+        assert_eq!(
+            active_seconds_between(0, 4 * DAY, monday_only, ALL_HOURS),
+            0
+        );
+        assert_eq!(
+            active_seconds_between(4 * DAY, 5 * DAY, monday_only, ALL_HOURS),
+            DAY
+        );
+        assert_eq!(
+            active_seconds_between(0, SECONDS_PER_WEEK, monday_only, ALL_HOURS),
+            DAY
+        );
+    }
+
+    #[test]
+    fn whole_weeks_are_counted_in_closed_form() {
+        // Weekdays, 08:00-15:59.
+        let (days, hours) = (0x1f, 0x00_ff00);
+        let from = 12_345;
+        assert_eq!(
+            active_seconds_between(from, from + 10 * SECONDS_PER_WEEK, days, hours),
+            10 * 5 * 8 * HOUR
+        );
+    }
+
+    #[test]
+    fn empty_ranges_and_masks_have_no_active_seconds() {
+        assert_eq!(active_seconds_between(10, 10, ALL_DAYS, ALL_HOURS), 0);
+        assert_eq!(active_seconds_between(10, 5, ALL_DAYS, ALL_HOURS), 0);
+        assert_eq!(active_seconds_between(0, SECONDS_PER_WEEK, 0, ALL_HOURS), 0);
+        assert_eq!(active_seconds_between(0, SECONDS_PER_WEEK, ALL_DAYS, 0), 0);
+    }
+
+    #[test]
+    fn full_mask_counts_every_second() {
+        assert_eq!(
+            active_seconds_between(7, 7 + 3 * SECONDS_PER_WEEK + 99, ALL_DAYS, ALL_HOURS),
+            3 * SECONDS_PER_WEEK + 99
+        );
+    }
+
+    #[test]
+    fn extreme_timestamps_do_not_overflow() {
+        assert_eq!(
+            active_seconds_between(u64::MAX - 1_000, u64::MAX, ALL_DAYS, ALL_HOURS),
+            1_000
+        );
+        let active = active_seconds_between(0, u64::MAX, ALL_DAYS, EVEN_HOURS);
+        assert!(active > 0 && active < u64::MAX);
+    }
+
+    #[test]
+    fn accrues_only_in_even_hours() {
+        // Four hours with rate 2: only hours 0 and 2 accrue, so the deposit is 2 * 2h.
+        let (start, end, rate) = (0, 4 * HOUR, 2);
+        let deposit = rate * (2 * HOUR) as i128;
+        let accrued = |now| {
+            accrue_scheduled_from_checkpoint(
+                0, start, start, end, rate, deposit, ALL_DAYS, EVEN_HOURS, now,
+            )
+        };
+
+        assert_eq!(accrued(HOUR / 2), 3_600);
+        assert_eq!(accrued(HOUR), 7_200);
+        // Hour 1 is inactive, so nothing more accrues during it.
+        assert_eq!(accrued(HOUR + HOUR / 2), 7_200);
+        assert_eq!(accrued(2 * HOUR + HOUR / 2), 10_800);
+        assert_eq!(accrued(end), deposit);
+        assert_eq!(accrued(end + DAY), deposit);
+    }
+
+    #[test]
+    fn continues_from_a_checkpoint() {
+        let (start, end, rate) = (0, 4 * HOUR, 2);
+        let deposit = rate * (2 * HOUR) as i128;
+        let closed_form = accrue_scheduled_from_checkpoint(
+            0,
+            start,
+            start,
+            end,
+            rate,
+            deposit,
+            ALL_DAYS,
+            EVEN_HOURS,
+            2 * HOUR + 600,
+        );
+        let checkpointed = accrue_scheduled_from_checkpoint(
+            7_200,
+            HOUR + HOUR / 2,
+            start,
+            end,
+            rate,
+            deposit,
+            ALL_DAYS,
+            EVEN_HOURS,
+            2 * HOUR + 600,
+        );
+        assert_eq!(checkpointed, closed_form);
+        assert_eq!(closed_form, 8_400);
+    }
+
+    #[test]
+    fn nothing_accrues_before_the_cliff() {
+        let accrued = accrue_scheduled_from_checkpoint(
+            0,
+            0,
+            2 * HOUR,
+            4 * HOUR,
+            1,
+            7_200,
+            ALL_DAYS,
+            EVEN_HOURS,
+            2 * HOUR - 1,
+        );
+        assert_eq!(accrued, 0);
+    }
+
+    #[test]
+    fn capped_at_deposit() {
+        let deposit = 1_000;
+        for rate in [1_000_000, i128::MAX] {
+            let accrued = accrue_scheduled_from_checkpoint(
+                0,
+                0,
+                0,
+                4 * HOUR,
+                rate,
+                deposit,
+                ALL_DAYS,
+                EVEN_HOURS,
+                HOUR,
+            );
+            assert_eq!(accrued, deposit);
+        }
+    }
+}
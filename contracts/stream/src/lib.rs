@@ -3,9 +3,22 @@
 mod accrual;
 
 use soroban_sdk::{
-    contract, contractimpl, contracttype, panic_with_error, symbol_short, token, Address, Env,
+    contract, contractimpl, contracttype, panic_with_error, symbol_short, token, Address, Bytes,
+    BytesN, Env, IntoVal, Symbol, Vec, I256,
 };
 
+/// Maximum number of streams `get_streams_page` returns in one call.
+pub const MAX_STREAMS_PAGE: u32 = 50;
+
+/// XDR encoding of `Asset::Native` (the `ASSET_TYPE_NATIVE` discriminant, `0`).
+const NATIVE_ASSET_XDR: [u8; 4] = [0; 4];
+
+/// Number of most recent withdrawals kept in a stream's withdraw log.
+pub const MAX_WITHDRAW_LOG: u32 = 50;
+
+/// Category given to streams created without `CreateStreamOptions::category`.
+pub const DEFAULT_CATEGORY: Symbol = symbol_short!("general");
+
 // ---------------------------------------------------------------------------
 // Data types
 // ---------------------------------------------------------------------------
@@ -16,6 +29,39 @@ use soroban_sdk::{
 pub struct Config {
     pub token: Address,
     pub admin: Address,
+    /// Share of every new stream's deposit taken at creation, in basis points.
+    pub creation_fee_bps: u32,
+    /// Receives creation fees.
+    pub fee_collector: Address,
+    /// Largest deposit a single stream may hold; `0` means unlimited.
+    pub max_deposit: i128,
+    /// Rounding applied to per-period rates and creation fees.
+    pub rounding_mode: RoundingMode,
+    /// Shortest `end_time - start_time` a new stream may have; `0` means no minimum.
+    pub min_duration_seconds: u64,
+    /// Seconds after cancelling a stream before the sender may create another; `0`
+    /// disables the cooldown.
+    pub cancel_cooldown_seconds: u64,
+    /// Only senders added with `add_allowed_creator` may create streams.
+    pub creators_allowlist: bool,
+    /// Move a `Cancelled` stream to `Settled` once its accrued amount is withdrawn.
+    pub settle_cancelled: bool,
+    /// Hold the recipient's payout from `cancel_full` in escrow until the recipient
+    /// claims it with `claim_cancelled`.
+    pub cancel_payout_ack: bool,
+    /// Reject new deposits (stream creation, funding, top-ups); withdrawals and
+    /// cancellations keep working.
+    pub deposits_paused: bool,
+}
+
+/// `Config` as written by the original release, still stored under `DataKey::Config`
+/// by contracts upgraded from it. Read through `get_config`, which fills in the
+/// defaults for every later setting.
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct LegacyConfig {
+    pub token: Address,
+    pub admin: Address,
 }
 
 #[contracttype]
@@ -25,6 +71,13 @@ pub enum StreamStatus {
     Paused = 1,
     Completed = 2,
     Cancelled = 3,
+    /// Created with `requires_acceptance`; nothing accrues until the recipient accepts.
+    PendingAcceptance = 4,
+    /// Created with `create_unfunded_stream`; nothing accrues until `fund_stream`.
+    Unfunded = 5,
+    /// A `Cancelled` stream whose accrued amount has been fully withdrawn. Only reached
+    /// while `Config::settle_cancelled` is enabled.
+    Settled = 6,
 }
 
 #[soroban_sdk::contracterror]
@@ -33,6 +86,143 @@ pub enum StreamStatus {
 pub enum ContractError {
     StreamNotFound = 1,
     InvalidState = 2,
+    /// The stream party expected to authorize the call cannot do so (e.g. the
+    /// party is this contract itself, which can never sign a direct invocation).
+    Unauthorized = 3,
+    /// The stream token is not on the admin-managed token whitelist.
+    TokenNotAllowed = 4,
+    /// The recipient's auto-withdraw authorization window has passed.
+    AuthorizationExpired = 5,
+    /// `create_streams` was called with no streams.
+    EmptyBatch = 6,
+    /// The summed deposits of a `create_streams` batch overflow.
+    ///
+    /// Reserved: batch totals are now summed as `I256`, which cannot overflow for any
+    /// batch of `i128` deposits, so this is no longer returned.
+    BatchOverflow = 7,
+    /// The stream's `min_withdraw_interval` has not elapsed since the last withdrawal.
+    WithdrawTooSoon = 8,
+    /// The withdrawable amount is positive but below the stream's `min_withdraw_amount`.
+    BelowMinWithdraw = 9,
+    /// Reserved: no entrypoint can leave a stream's deposit short of its schedule, so
+    /// there is no shortfall top-up to reject and this is no longer returned.
+    AlreadyFullyFunded = 10,
+    /// `set_admin` was called with the current admin address.
+    SameAdmin = 11,
+    /// `start_time` lies further before the current ledger time than the configured
+    /// `max_backdating_seconds` allows.
+    StartTooFarInPast = 12,
+    /// The contract has not been initialised with `init`.
+    NotInitialized = 13,
+    /// Sweeping is disabled for the stream or its sweep window has not opened yet.
+    SweepNotAvailable = 14,
+    /// The contract's token balance cannot cover an outgoing transfer, meaning its
+    /// accounting has drifted from the balance actually held.
+    InsufficientContractBalance = 15,
+    /// The recipient is this contract or the stream token, which could never claim
+    /// the funds.
+    InvalidRecipient = 16,
+    /// `finalize_cancel` was called before the stream's `cancel_effective_at`.
+    CancelNotYetEffective = 17,
+    /// `sweep_stray_tokens` would take tokens still owed to streams.
+    SweepExceedsStray = 18,
+    /// `NextStreamId` has reached `u64::MAX`; no further stream ids can be issued.
+    StreamIdExhausted = 19,
+    /// Invariant check: `withdrawn_amount > deposit_amount`.
+    WithdrawnExceedsDeposit = 20,
+    /// Invariant check: `start_time >= end_time`.
+    InvalidTimeRange = 21,
+    /// Invariant check: `cliff_time` lies outside `[start_time, end_time]`.
+    CliffOutOfRange = 22,
+    /// Invariant check: `withdrawn_amount > calculate_accrued`.
+    WithdrawnExceedsAccrued = 23,
+    /// The `token` passed to `init` does not answer the token interface.
+    InvalidToken = 24,
+    /// The stream is `Completed`; nothing is left to withdraw.
+    AlreadyCompleted = 25,
+    /// The stream is `Paused`; withdrawals resume with the stream.
+    StreamPaused = 26,
+    /// The stream's deposit would exceed the admin-configured `max_deposit`.
+    DepositExceedsCap = 27,
+    /// The stream's duration is below the admin-configured `min_duration_seconds`.
+    DurationTooShort = 28,
+    /// The stream's `start_time` has been reached; its recipient can no longer change.
+    StreamAlreadyStarted = 29,
+    /// `rate_per_second * (end_time - start_time)` does not fit in an `i128`.
+    Overflow = 30,
+    /// Non-terminal streams still hold deposits in the current token.
+    TokenInUse = 31,
+    /// A withdrawal was attempted while a withdraw hook was running.
+    Reentrant = 32,
+    /// The sender cancelled a stream less than `cancel_cooldown_seconds` ago.
+    CancelCooldown = 33,
+    /// `validate_create` found a parameter that `create_stream` rejects with a panic
+    /// message rather than a contract error.
+    InvalidParams = 34,
+    /// The creator allowlist is enabled and the sender is not on it.
+    CreatorNotAllowed = 35,
+    /// The admin has paused deposits; creating or topping up streams is disabled.
+    DepositsPaused = 36,
+    /// An entry of a `create_streams` batch failed validation. The entry's index and
+    /// error are published in a `StreamEvent::BatchItemRejected` event just before the
+    /// revert.
+    BatchItemInvalid = 1000,
+}
+
+/// Payload of `StreamEvent::AdminRotated`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AdminRotated {
+    pub old: Address,
+    pub new: Address,
+}
+
+/// Payload of `StreamEvent::CancelledWithReason`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CancelledWithReason {
+    pub stream_id: u64,
+    /// Operator-defined reason code passed to `cancel_stream_as_admin`.
+    pub reason: u32,
+}
+
+/// Payload of `StreamEvent::Extended`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Extended {
+    pub stream_id: u64,
+    /// The stream's new `end_time`.
+    pub end_time: u64,
+    /// Amount added to the stream's deposit.
+    pub amount: i128,
+}
+
+/// Payload of `StreamEvent::RecipientAssigned`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RecipientAssigned {
+    pub stream_id: u64,
+    pub old: Address,
+    pub new: Address,
+}
+
+/// Payload of `StreamEvent::SenderTransferred`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SenderTransferred {
+    pub stream_id: u64,
+    pub old: Address,
+    pub new: Address,
+}
+
+/// Payload of `StreamEvent::BatchItemRejected`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BatchItemRejected {
+    /// Position of the failing entry in the batch.
+    pub index: u32,
+    /// Code of the `ContractError` that `validate_create` reports for the entry.
+    pub error: u32,
 }
 
 #[contracttype]
@@ -41,6 +231,127 @@ pub enum StreamEvent {
     Paused(u64),
     Resumed(u64),
     Cancelled(u64),
+    Accepted(u64),
+    Rejected(u64),
+    AdminRotated(AdminRotated),
+    Completed(u64),
+    Renounced(u64),
+    CancelledWithReason(CancelledWithReason),
+    PauseRequested(u64),
+    Revoked(u64),
+    Extended(Extended),
+    RecipientAssigned(RecipientAssigned),
+    SenderTransferred(SenderTransferred),
+    Settled(u64),
+    BatchItemRejected(BatchItemRejected),
+}
+
+/// Parameters of `AccrualKind::FrontLoaded`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FrontLoaded {
+    /// Seconds after which the release rate halves; must be positive.
+    pub halflife_seconds: u64,
+}
+
+/// Parameters of `AccrualKind::Scheduled`: the hours of the week, in UTC, during which
+/// a stream accrues.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ScheduleMask {
+    /// Bit `d` enables weekday `d`, from `0` = Monday to `6` = Sunday.
+    pub days: u32,
+    /// Bit `h` enables the hour `h:00`-`h:59` on every enabled day.
+    pub hours: u32,
+}
+
+/// Shape of a stream's release curve between `start_time` and `end_time`.
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub enum AccrualKind {
+    /// Constant `rate_per_second` release.
+    #[default]
+    Linear,
+    /// Release rate halves every `halflife_seconds`, rescaled so the whole deposit is
+    /// released exactly at `end_time`.
+    FrontLoaded(FrontLoaded),
+    /// Time-lock: nothing before `cliff_time`, the whole deposit from it on.
+    /// `rate_per_second` is not used and is stored as `0`.
+    SingleUnlock,
+    /// Constant `rate_per_second` release during the active hours of the mask only;
+    /// the clock stops outside them.
+    Scheduled(ScheduleMask),
+}
+
+/// Direction in which a division that does not come out even is rounded.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum RoundingMode {
+    /// Drop the remainder, as plain integer division does.
+    #[default]
+    Floor,
+    /// Round any remainder up.
+    Ceil,
+    /// Round to the closest integer, halves up.
+    Nearest,
+}
+
+/// Parameters for one stream in a `create_streams` batch.
+///
+/// Mirrors the arguments of `create_stream`; the sender is shared by the batch.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CreateStreamParams {
+    pub recipient: Address,
+    pub deposit_amount: i128,
+    pub rate_per_second: i128,
+    pub start_time: u64,
+    pub cliff_time: u64,
+    pub end_time: u64,
+}
+
+/// Outcome of one stream created by `create_streams_with_receipts`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CreateStreamReceipt {
+    pub stream_id: u64,
+    /// `rate_per_second * (end_time - start_time)`.
+    pub total_streamable: i128,
+    /// Stored deposit (net of any creation fee) above `total_streamable`.
+    pub excess: i128,
+}
+
+/// Optional creation settings for `create_stream_with_options`.
+///
+/// `Default` gives the behaviour of plain `create_stream`.
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct CreateStreamOptions {
+    /// Start in `PendingAcceptance` until the recipient calls `accept_stream`.
+    pub requires_acceptance: bool,
+    /// Minimum seconds between two withdrawals; `0` disables the cooldown.
+    pub min_withdraw_interval: u64,
+    /// Smallest amount a single withdrawal may move; `0` disables the minimum.
+    pub min_withdraw_amount: i128,
+    /// Seconds after `end_time` from which the sender may sweep unclaimed accrual back;
+    /// `0` disables sweeping.
+    pub unclaimed_sweep_after: u64,
+    /// Amount that unlocks at once when the cliff is reached, on top of linear accrual;
+    /// must be within `[0, deposit_amount]`. Never released if the stream is cancelled
+    /// before it starts or is accepted.
+    pub cliff_unlock_amount: i128,
+    /// Release curve; `Linear` by default.
+    pub accrual_kind: AccrualKind,
+    /// Most the recipient can ever accrue; the rest of the deposit is a buffer returned
+    /// to the sender. Must be within `[0, deposit_amount]`; `0` means the whole deposit.
+    pub max_withdrawable: i128,
+    /// Reporting label such as `payroll` or `grants`; `DEFAULT_CATEGORY` when `None`.
+    pub category: Option<Symbol>,
+    /// Move `end_time` out so the whole deposit streams at `rate_per_second`
+    /// (`Linear` only).
+    pub auto_extend: bool,
+    /// Refundable amount held on top of `deposit_amount` and never streamed.
+    pub security_deposit: i128,
 }
 
 #[contracttype]
@@ -57,25 +368,208 @@ pub struct Stream {
     pub withdrawn_amount: i128,
     pub status: StreamStatus,
     pub cancelled_at: Option<u64>,
+    pub completed_at: Option<u64>,
+    pub min_withdraw_interval: u64,
+    pub last_withdraw_time: Option<u64>,
+    pub min_withdraw_amount: i128,
+    /// Amount accrued as of `checkpoint_time`; accrual continues from here.
+    pub accrued_checkpoint: i128,
+    pub checkpoint_time: u64,
+    pub unclaimed_sweep_after: u64,
+    /// Accrued amount returned to the sender by `sweep_unclaimed`; also counted in
+    /// `withdrawn_amount` so `accrued - withdrawn_amount` stays the claimable balance.
+    pub swept_amount: i128,
+    /// Upfront amount released at the cliff; seeds `accrued_checkpoint` at creation.
+    pub cliff_unlock_amount: i128,
+    pub accrual_kind: AccrualKind,
+    /// Ledger timestamp of the last write of this stream; set by `save_stream`.
+    pub last_updated: u64,
+    /// Time at which a cancellation started with `initiate_cancel` takes effect;
+    /// accrual stops there and `finalize_cancel` becomes available.
+    pub cancel_effective_at: Option<u64>,
+    /// Ceiling on accrual (`<= deposit_amount`); `deposit_amount - max_withdrawable` is
+    /// refunded to the sender on completion or cancellation. `0` for a stream cancelled
+    /// before acceptance or funding, which never accrues.
+    pub max_withdrawable: i128,
+    /// Set by the recipient's `request_pause`; cleared when the stream is paused.
+    pub pause_requested: bool,
+    /// Reporting label fixed at creation; indexed by `get_streams_by_category`.
+    pub category: Symbol,
+    /// When the stream was last paused; `resume_stream_extend` measures the pause from it.
+    pub paused_at: Option<u64>,
+    /// Contract notified with `stream_received(stream_id, amount)` after each payout;
+    /// set by the recipient with `set_withdraw_hook`.
+    pub on_withdraw_hook: Option<Address>,
+    /// Held outside accrual; refunded to the sender when the stream ends unless
+    /// `forfeit_deposit_as_admin` pays it to the recipient first. `0` once settled.
+    pub security_deposit: i128,
+    /// Ledger timestamp at which the stream was created and its deposit locked; earlier
+    /// than `start_time` for a future-dated stream, which accrues nothing in between.
+    pub created_at: u64,
+}
+
+/// A `Stream` together with its accrual at the time of the call.
+///
+/// Returned by `get_stream_full`; computed on read and never stored.
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct StreamView {
+    pub stream: Stream,
+    /// `calculate_accrued` at the time of the call.
+    pub accrued: i128,
+    /// `accrued - withdrawn_amount`: what the recipient is owed right now.
+    pub withdrawable: i128,
+}
+
+/// `Stream` as written by the original release under `DataKey::Stream(stream_id)`.
+/// `load_stream` still reads such entries; the next `save_stream` moves them to
+/// `DataKey::StreamV2`.
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct LegacyStream {
+    pub stream_id: u64,
+    pub sender: Address,
+    pub recipient: Address,
+    pub deposit_amount: i128,
+    pub rate_per_second: i128,
+    pub start_time: u64,
+    pub cliff_time: u64,
+    pub end_time: u64,
+    pub withdrawn_amount: i128,
+    pub status: StreamStatus,
+    pub cancelled_at: Option<u64>,
 }
 
+/// Storage form of `Stream`, written under `DataKey::StreamV2(stream_id)`.
+///
+/// A named-field struct is stored as a map keyed by field name, so every entry pays
+/// for those names; this tuple struct is stored as a plain vector instead. The
+/// `stream_id` is the storage key and is not repeated, and the status and the
+/// presence of the optional timestamps share one flags word. Timestamps are kept
+/// absolute: every `u64` encodes to 8 bytes, so deltas would not shrink the entry.
+///
+/// Element order: `sender, recipient, deposit_amount, rate_per_second, start_time,
+/// cliff_time, end_time, withdrawn_amount, flags, cancelled_at, completed_at,
+/// min_withdraw_interval, last_withdraw_time, min_withdraw_amount, accrued_checkpoint,
+/// checkpoint_time, unclaimed_sweep_after, swept_amount, cliff_unlock_amount,
+/// accrual_kind, last_updated, cancel_effective_at, max_withdrawable, category,
+/// paused_at, on_withdraw_hook, security_deposit, created_at`. Absent optional timestamps are
+/// stored as `0`. Convert with `pack_stream` / `unpack_stream`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PackedStream(
+    pub Address,
+    pub Address,
+    pub i128,
+    pub i128,
+    pub u64,
+    pub u64,
+    pub u64,
+    pub i128,
+    pub u32,
+    pub u64,
+    pub u64,
+    pub u64,
+    pub u64,
+    pub i128,
+    pub i128,
+    pub u64,
+    pub u64,
+    pub i128,
+    pub i128,
+    pub AccrualKind,
+    pub u64,
+    pub u64,
+    pub i128,
+    pub Symbol,
+    pub u64,
+    pub Option<Address>,
+    pub i128,
+    pub u64,
+);
+
+/// `PackedStream` flags: the low bits hold the `StreamStatus` discriminant.
+const STATUS_MASK: u32 = 0b111;
+const HAS_CANCELLED_AT: u32 = 1 << 3;
+const HAS_COMPLETED_AT: u32 = 1 << 4;
+const HAS_LAST_WITHDRAW_TIME: u32 = 1 << 5;
+const HAS_CANCEL_EFFECTIVE_AT: u32 = 1 << 6;
+const PAUSE_REQUESTED: u32 = 1 << 7;
+const HAS_PAUSED_AT: u32 = 1 << 8;
+
 /// Namespace for all contract storage keys.
 #[contracttype]
 pub enum DataKey {
-    Config,       // Instance storage for global settings (admin/token).
-    NextStreamId, // Instance storage for the auto-incrementing ID counter.
-    Stream(u64),  // Persistent storage for individual stream data (O(1) lookup).
+    Config,                       // Instance storage for the original `LegacyConfig`.
+    NextStreamId,                 // Instance storage for the auto-incrementing ID counter.
+    Stream(u64),                  // Persistent storage for an original `LegacyStream`.
+    AllowedToken(Address),        // Persistent storage marking a whitelisted stream token.
+    AllowedTokenCount,            // Instance storage for the number of whitelisted tokens.
+    AutoWithdrawUntil(u64),       // Persistent storage for a recipient's auto-withdraw window end.
+    ActiveStreamCount,            // Instance storage for the number of non-terminal streams.
+    MaxBackdating,                // Instance storage for max start_time backdating (0 = off).
+    SplitWeights(u64),            // Persistent storage for a split stream's weight table.
+    SplitWithdrawn(u64, Address), // Persistent storage for a split recipient's withdrawn sum.
+    TotalDeposited,               // Instance storage (I256) for all stream deposits ever received.
+    TotalWithdrawn,               // Instance storage (I256) for stream tokens paid to recipients.
+    TotalRefunded,                // Instance storage (I256) for stream tokens returned to senders.
+    Treasury,                     // Instance storage for the stray-token sweep destination.
+    CategoryStreams(Symbol),      // Persistent storage for the ids of streams in a category.
+    RecipientStreams(Address),    // Persistent storage for the ids of a recipient's streams.
+    SenderStreams(Address),       // Persistent storage for the ids of a sender's streams.
+    WithdrawLog(u64),             // Persistent storage for a stream's recent withdrawals.
+    IdemKey(Address, BytesN<32>), // Persistent storage for the stream a sender created under a key.
+    WithdrawLock,                 // Temporary storage set while a withdraw hook runs.
+    LastCancel(Address),          // Persistent storage for a sender's last cancellation time.
+    StatusCount(StreamStatus),    // Instance storage for the number of streams in a status.
+    AllowedCreator(Address),      // Persistent storage marking a sender allowed to create streams.
+    CancelEscrow(u64), // Persistent storage for a cancel payout awaiting claim_cancelled.
+    ConfigV2,          // Instance storage for global settings (`Config`).
+    StreamV2(u64),     // Persistent storage for individual stream data (`PackedStream`).
 }
 
 // ---------------------------------------------------------------------------
 // Storage helpers
 // ---------------------------------------------------------------------------
 
+/// Settings of a freshly initialised contract.
+fn default_config(token: Address, admin: Address) -> Config {
+    Config {
+        token,
+        fee_collector: admin.clone(),
+        admin,
+        creation_fee_bps: 0,
+        max_deposit: 0,
+        rounding_mode: RoundingMode::Floor,
+        min_duration_seconds: 0,
+        cancel_cooldown_seconds: 0,
+        creators_allowlist: false,
+        settle_cancelled: false,
+        cancel_payout_ack: false,
+        deposits_paused: false,
+    }
+}
+
+/// The stored `Config`, or the defaults around a `LegacyConfig` left by the original
+/// release; `None` before `init`.
+fn try_get_config(env: &Env) -> Option<Config> {
+    let storage = env.storage().instance();
+    storage.get(&DataKey::ConfigV2).or_else(|| {
+        storage
+            .get::<_, LegacyConfig>(&DataKey::Config)
+            .map(|legacy| default_config(legacy.token, legacy.admin))
+    })
+}
+
 fn get_config(env: &Env) -> Config {
-    env.storage()
-        .instance()
-        .get(&DataKey::Config)
-        .expect("contract not initialised: missing config")
+    try_get_config(env).expect("contract not initialised: missing config")
+}
+
+/// Store `config`, retiring any `LegacyConfig` it replaces.
+fn set_config(env: &Env, config: &Config) {
+    let storage = env.storage().instance();
+    storage.set(&DataKey::ConfigV2, config);
+    storage.remove(&DataKey::Config);
 }
 
 fn get_token(env: &Env) -> Address {
@@ -86,6 +580,63 @@ fn get_admin(env: &Env) -> Address {
     get_config(env).admin
 }
 
+/// Whether `deposit_amount` is above the configured per-stream cap (`0` = none).
+fn exceeds_deposit_cap(env: &Env, deposit_amount: i128) -> bool {
+    let max_deposit = get_config(env).max_deposit;
+    max_deposit > 0 && deposit_amount > max_deposit
+}
+
+/// Whether a schedule from `start_time` to `end_time` is shorter than the configured
+/// minimum (`0` = none).
+fn is_duration_too_short(env: &Env, start_time: u64, end_time: u64) -> bool {
+    end_time.saturating_sub(start_time) < get_config(env).min_duration_seconds
+}
+
+/// Remember when `sender` last cancelled one of their streams.
+fn record_sender_cancel(env: &Env, sender: &Address) {
+    let key = DataKey::LastCancel(sender.clone());
+    env.storage()
+        .persistent()
+        .set(&key, &env.ledger().timestamp());
+    env.storage().persistent().extend_ttl(&key, 17280, 120960);
+}
+
+/// Whether `sender` cancelled a stream within the configured cooldown (`0` = none).
+fn is_in_cancel_cooldown(env: &Env, sender: &Address) -> bool {
+    let cooldown = get_config(env).cancel_cooldown_seconds;
+    if cooldown == 0 {
+        return false;
+    }
+    env.storage()
+        .persistent()
+        .get::<_, u64>(&DataKey::LastCancel(sender.clone()))
+        .is_some_and(|last| env.ledger().timestamp() < last.saturating_add(cooldown))
+}
+
+/// `rate_per_second * (end_time - start_time)`, the deposit a linear schedule needs;
+/// `None` on overflow. An empty or inverted schedule streams nothing.
+fn total_streamable(rate_per_second: i128, start_time: u64, end_time: u64) -> Option<i128> {
+    rate_per_second.checked_mul(end_time.saturating_sub(start_time) as i128)
+}
+
+/// The deposit a stream's release curve needs: `total_streamable` for every curve but
+/// `Scheduled`, which only streams during the active seconds of its mask.
+fn curve_total_streamable(
+    accrual_kind: &AccrualKind,
+    rate_per_second: i128,
+    start_time: u64,
+    end_time: u64,
+) -> Option<i128> {
+    match accrual_kind {
+        AccrualKind::Scheduled(mask) => {
+            let active =
+                accrual::active_seconds_between(start_time, end_time, mask.days, mask.hours);
+            rate_per_second.checked_mul(active as i128)
+        }
+        _ => total_streamable(rate_per_second, start_time, end_time),
+    }
+}
+
 fn get_stream_count(env: &Env) -> u64 {
     env.storage()
         .instance()
@@ -97,185 +648,4309 @@ fn set_stream_count(env: &Env, count: u64) {
     env.storage().instance().set(&DataKey::NextStreamId, &count);
 }
 
-fn load_stream(env: &Env, stream_id: u64) -> Result<Stream, ContractError> {
+/// Number of streams that are not yet `Completed` or `Cancelled`.
+fn get_active_stream_count(env: &Env) -> u64 {
     env.storage()
-        .persistent()
-        .get(&DataKey::Stream(stream_id))
-        .ok_or(ContractError::StreamNotFound)
+        .instance()
+        .get(&DataKey::ActiveStreamCount)
+        .unwrap_or(0u64)
 }
 
-fn save_stream(env: &Env, stream: &Stream) {
-    let key = DataKey::Stream(stream.stream_id);
-    env.storage().persistent().set(&key, stream);
-
-    // Requirement from Issue #1: extend TTL on stream save to ensure persistence
-    env.storage().persistent().extend_ttl(&key, 17280, 120960);
+fn set_active_stream_count(env: &Env, count: u64) {
+    env.storage()
+        .instance()
+        .set(&DataKey::ActiveStreamCount, &count);
 }
 
-// ---------------------------------------------------------------------------
-// Contract Implementation
-// ---------------------------------------------------------------------------
+/// Record a stream leaving the non-terminal set.
+fn decrement_active_stream_count(env: &Env) {
+    set_active_stream_count(env, get_active_stream_count(env).saturating_sub(1));
+}
 
-#[contract]
-pub struct FluxoraStream;
+/// Number of streams currently in `status`.
+fn get_status_count(env: &Env, status: StreamStatus) -> u64 {
+    env.storage()
+        .instance()
+        .get(&DataKey::StatusCount(status))
+        .unwrap_or(0u64)
+}
 
-#[contractimpl]
-impl FluxoraStream {
-    /// Initialise the contract with the streaming token and admin address.
-    ///
-    /// This function must be called exactly once before any other contract operations.
-    /// It persists the token address (used for all stream transfers) and admin address
-    /// (authorized for administrative operations) in instance storage.
-    ///
-    /// # Parameters
-    /// - `token`: Address of the token contract used for all payment streams
-    /// - `admin`: Address authorized to perform administrative operations (pause, cancel, etc.)
-    ///
-    /// # Storage
-    /// - Stores `Config { token, admin }` in instance storage under `DataKey::Config`
-    /// - Initializes `NextStreamId` counter to 0 for stream ID generation
-    /// - Extends TTL to prevent premature expiration (17280 ledgers threshold, 120960 max)
-    ///
-    /// # Panics
-    /// - If called more than once (contract already initialized)
-    ///
-    /// # Security
-    /// - Re-initialization is prevented to ensure immutable token and admin configuration
-    /// - No authorization required for initial setup (deployer calls this once)
-    pub fn init(env: Env, token: Address, admin: Address) {
-        if env.storage().instance().has(&DataKey::Config) {
-            panic!("already initialised");
-        }
-        let config = Config { token, admin };
-        env.storage().instance().set(&DataKey::Config, &config);
-        env.storage().instance().set(&DataKey::NextStreamId, &0u64);
+fn set_status_count(env: &Env, status: StreamStatus, count: u64) {
+    env.storage()
+        .instance()
+        .set(&DataKey::StatusCount(status), &count);
+}
 
-        // Ensure instance storage (Config/ID) doesn't expire quickly
-        env.storage().instance().extend_ttl(17280, 120960);
+/// Move one stream between the per-status counters (`None` for a new stream).
+fn move_status_count(env: &Env, from: Option<StreamStatus>, to: StreamStatus) {
+    if from == Some(to) {
+        return;
     }
+    if let Some(from) = from {
+        set_status_count(env, from, get_status_count(env, from).saturating_sub(1));
+    }
+    set_status_count(env, to, get_status_count(env, to).saturating_add(1));
+}
 
-    /// Create a new payment stream with specified parameters.
-    ///
-    /// Establishes a new token stream from sender to recipient with defined rate and duration.
-    /// Transfers the deposit amount from sender to the contract immediately. Returns a unique
-    /// stream ID that can be used to interact with the stream.
-    ///
-    /// # Parameters
-    /// - `sender`: Address funding the stream (must authorize the transaction)
-    /// - `recipient`: Address receiving the streamed tokens
-    /// - `deposit_amount`: Total tokens to deposit (must be > 0)
-    /// - `rate_per_second`: Streaming rate in tokens per second (must be > 0)
-    /// - `start_time`: When streaming begins (ledger timestamp)
-    /// - `cliff_time`: When tokens first become available (vesting cliff, must be in [start_time, end_time])
-    /// - `end_time`: When streaming completes (must be > start_time)
-    ///
-    /// # Returns
-    /// - `u64`: Unique stream identifier for the newly created stream
-    ///
-    /// # Authorization
-    /// - Requires authorization from the sender address
-    ///
-    /// # Validation
-    /// The function validates all parameters before creating the stream:
-    /// - `deposit_amount > 0` and `rate_per_second > 0`
-    /// - `sender != recipient` (cannot stream to yourself)
-    /// - `start_time < end_time` (valid time range)
-    /// - `cliff_time` in `[start_time, end_time]` (cliff within stream duration)
-    /// - `deposit_amount >= rate_per_second × (end_time - start_time)` (sufficient deposit)
-    ///
-    /// # Panics
-    /// - If `deposit_amount` or `rate_per_second` is not positive
-    /// - If `sender` and `recipient` are the same address
-    /// - If `start_time >= end_time` (invalid time range)
-    /// - If `cliff_time` is not in `[start_time, end_time]`
-    /// - If `deposit_amount < rate_per_second × (end_time - start_time)` (insufficient deposit)
-    /// - If token transfer fails (insufficient balance or allowance)
-    /// - If overflow occurs calculating total streamable amount
-    ///
-    /// # State Changes
-    /// - Transfers `deposit_amount` tokens from sender to contract
-    /// - Creates new stream with status `Active`
-    /// - Increments global stream counter
-    /// - Stores stream data in persistent storage with extended TTL
-    ///
-    /// # Events
-    /// - Publishes `created(stream_id, deposit_amount)` event on success
-    ///
-    /// # Usage Notes
-    /// - Transaction is atomic: if token transfer fails, no stream is created
-    /// - Stream IDs are sequential starting from 0
-    /// - Cliff time enables vesting schedules (no withdrawals before cliff)
-    /// - Setting `cliff_time = start_time` means no cliff (immediate vesting)
-    /// - Deposit can exceed minimum required (excess remains in contract)
-    /// - Sender must have sufficient token balance and approve contract
-    /// ## Stream Limits Policy
-    /// No hard upper bounds are enforced on `deposit_amount` or stream duration.
-    /// Rationale:
-    /// - Overflow in accrual math is already prevented via `checked_mul` and clamping.
-    /// - A fixed cap would require a contract upgrade to change and conflicts with
-    ///   the overflow test suite, which exercises values up to `i128::MAX`.
-    /// - Protocol-specific limits (e.g. "max 10 M USDC per stream") belong at the
-    ///   application layer (UI or an admin-gated factory contract), where business
-    ///   context is available.
-    ///
-    /// Senders are responsible for the correctness of the values they supply.
-    /// The validations above (`deposit > 0`, `rate > 0`, `deposit >= rate × duration`,
-    /// valid time window) are the contract's complete set of creation constraints.
-    ///
-    /// # Examples
-    /// - Linear stream: 1000 tokens over 1000 seconds, no cliff
-    ///   - `deposit_amount = 1000`, `rate = 1`, `start = 0`, `cliff = 0`, `end = 1000`
-    /// - Vesting stream: 12000 tokens over 12 months, 6-month cliff
-    ///   - `deposit_amount = 12000`, `rate = 1`, `start = 0`, `cliff = 15552000`, `end = 31104000`
-    #[allow(clippy::too_many_arguments)]
-    pub fn create_stream(
-        env: Env,
-        sender: Address,
-        recipient: Address,
-        deposit_amount: i128,
-        rate_per_second: i128,
-        start_time: u64,
-        cliff_time: u64,
-        end_time: u64,
-    ) -> u64 {
-        sender.require_auth();
+fn get_allowed_token_count(env: &Env) -> u32 {
+    env.storage()
+        .instance()
+        .get(&DataKey::AllowedTokenCount)
+        .unwrap_or(0u32)
+}
 
-        // Validate positive amounts (#35)
-        assert!(deposit_amount > 0, "deposit_amount must be positive");
-        assert!(rate_per_second > 0, "rate_per_second must be positive");
+fn set_allowed_token_count(env: &Env, count: u32) {
+    env.storage()
+        .instance()
+        .set(&DataKey::AllowedTokenCount, &count);
+}
+
+fn is_allowed_token(env: &Env, token: &Address) -> bool {
+    env.storage()
+        .persistent()
+        .has(&DataKey::AllowedToken(token.clone()))
+}
+
+/// A token may be streamed if it is whitelisted, or, while the whitelist is empty,
+/// if it is the config token.
+fn is_stream_token_allowed(env: &Env, token: &Address) -> bool {
+    if get_allowed_token_count(env) == 0 {
+        *token == get_token(env)
+    } else {
+        is_allowed_token(env, token)
+    }
+}
+
+fn require_token_allowed(env: &Env, token: &Address) {
+    if !is_stream_token_allowed(env, token) {
+        panic_with_error!(env, ContractError::TokenNotAllowed);
+    }
+}
+
+fn is_allowed_creator(env: &Env, sender: &Address) -> bool {
+    env.storage()
+        .persistent()
+        .has(&DataKey::AllowedCreator(sender.clone()))
+}
+
+/// With the creator allowlist enabled, only allowed senders may create streams.
+fn is_creator_permitted(env: &Env, sender: &Address) -> bool {
+    !get_config(env).creators_allowlist || is_allowed_creator(env, sender)
+}
+
+fn require_creator_permitted(env: &Env, sender: &Address) {
+    if !is_creator_permitted(env, sender) {
+        panic_with_error!(env, ContractError::CreatorNotAllowed);
+    }
+}
+
+fn require_deposits_open(env: &Env) {
+    if get_config(env).deposits_paused {
+        panic_with_error!(env, ContractError::DepositsPaused);
+    }
+}
+
+/// `numerator / denominator` rounded per `mode`, for `numerator >= 0` and
+/// `denominator > 0`.
+fn div_rounded(numerator: i128, denominator: i128, mode: RoundingMode) -> i128 {
+    let remainder = numerator % denominator;
+    let round_up = match mode {
+        RoundingMode::Floor => false,
+        RoundingMode::Ceil => remainder != 0,
+        RoundingMode::Nearest => remainder >= denominator - remainder,
+    };
+    numerator / denominator + i128::from(round_up)
+}
+
+/// Split a gross deposit into `(fee, net)` using the configured `creation_fee_bps`,
+/// rounding the fee per the configured `rounding_mode`.
+fn split_creation_fee(env: &Env, gross_deposit: i128) -> (i128, i128) {
+    let config = get_config(env);
+    let fee_bps = config.creation_fee_bps as i128;
+    if gross_deposit <= 0 || fee_bps == 0 {
+        return (0, gross_deposit);
+    }
+    // `gross * bps / 10000` without overflowing for large deposits; only the
+    // remainder term can be inexact.
+    let fee = (gross_deposit / 10_000) * fee_bps
+        + div_rounded(
+            (gross_deposit % 10_000) * fee_bps,
+            10_000,
+            config.rounding_mode,
+        );
+    (fee, gross_deposit - fee)
+}
+
+fn get_max_backdating(env: &Env) -> u64 {
+    env.storage()
+        .instance()
+        .get(&DataKey::MaxBackdating)
+        .unwrap_or(0u64)
+}
+
+/// Reject a `start_time` more than `max_backdating_seconds` before now, which would
+/// unlock most of a stream immediately. A limit of `0` disables the check.
+fn require_start_not_too_far_in_past(env: &Env, start_time: u64) {
+    if is_start_too_far_in_past(env, start_time) {
+        panic_with_error!(env, ContractError::StartTooFarInPast);
+    }
+}
+
+fn is_start_too_far_in_past(env: &Env, start_time: u64) -> bool {
+    let max_backdating = get_max_backdating(env);
+    max_backdating != 0 && start_time.saturating_add(max_backdating) < env.ledger().timestamp()
+}
+
+/// Recipients that can never claim a payout: this contract itself and the
+/// token contract. Funds streamed to either would be stuck.
+fn is_invalid_recipient(env: &Env, recipient: &Address) -> bool {
+    *recipient == env.current_contract_address() || *recipient == get_token(env)
+}
+
+/// A rejected set of stream creation parameters.
+enum InvalidParams {
+    /// Single-stream creation panics with this message.
+    Message(&'static str),
+    /// Single-stream creation fails with this contract error.
+    Error(ContractError),
+}
+
+fn ensure(condition: bool, message: &'static str) -> Result<(), InvalidParams> {
+    if condition {
+        Ok(())
+    } else {
+        Err(InvalidParams::Message(message))
+    }
+}
+
+impl InvalidParams {
+    /// The contract error `validate_create` reports for these parameters.
+    fn into_error(self) -> ContractError {
+        match self {
+            InvalidParams::Message(_) => ContractError::InvalidParams,
+            InvalidParams::Error(error) => error,
+        }
+    }
+}
+
+/// Fail a `create_streams` batch on entry `index`. The revert discards state but the
+/// event still reaches simulation and diagnostic output, so clients can tell which
+/// entry to fix and why.
+fn reject_batch_item(env: &Env, sender: &Address, index: u32, error: ContractError) -> ! {
+    env.events().publish(
+        (symbol_short!("batch_err"), sender.clone()),
+        StreamEvent::BatchItemRejected(BatchItemRejected {
+            index,
+            error: error as u32,
+        }),
+    );
+    panic_with_error!(env, ContractError::BatchItemInvalid)
+}
+
+fn load_split_weights(env: &Env, stream_id: u64) -> Option<Vec<(Address, u32)>> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::SplitWeights(stream_id))
+}
+
+fn get_split_withdrawn(env: &Env, stream_id: u64, recipient: &Address) -> i128 {
+    env.storage()
+        .persistent()
+        .get(&DataKey::SplitWithdrawn(stream_id, recipient.clone()))
+        .unwrap_or(0)
+}
+
+fn set_split_withdrawn(env: &Env, stream_id: u64, recipient: &Address, amount: i128) {
+    let key = DataKey::SplitWithdrawn(stream_id, recipient.clone());
+    env.storage().persistent().set(&key, &amount);
+    env.storage().persistent().extend_ttl(&key, 17280, 120960);
+}
+
+fn get_cancel_escrow(env: &Env, stream_id: u64) -> i128 {
+    env.storage()
+        .persistent()
+        .get(&DataKey::CancelEscrow(stream_id))
+        .unwrap_or(0)
+}
+
+fn set_cancel_escrow(env: &Env, stream_id: u64, amount: i128) {
+    let key = DataKey::CancelEscrow(stream_id);
+    env.storage().persistent().set(&key, &amount);
+    env.storage().persistent().extend_ttl(&key, 17280, 120960);
+}
+
+/// Running stream-token total; kept in `I256` because batches may deposit past
+/// `i128::MAX` in one call.
+fn get_total(env: &Env, key: &DataKey) -> I256 {
+    env.storage()
+        .instance()
+        .get(key)
+        .unwrap_or_else(|| I256::from_i128(env, 0))
+}
+
+fn add_to_total(env: &Env, key: DataKey, amount: i128) {
+    let total = get_total(env, &key).add(&I256::from_i128(env, amount));
+    env.storage().instance().set(&key, &total);
+}
+
+/// Stream tokens the contract still owes to streams:
+/// `TotalDeposited - TotalWithdrawn - TotalRefunded`.
+fn get_stream_obligations(env: &Env) -> I256 {
+    get_total(env, &DataKey::TotalDeposited)
+        .sub(&get_total(env, &DataKey::TotalWithdrawn))
+        .sub(&get_total(env, &DataKey::TotalRefunded))
+}
+
+fn get_treasury(env: &Env) -> Address {
+    env.storage()
+        .instance()
+        .get(&DataKey::Treasury)
+        .unwrap_or_else(|| get_admin(env))
+}
+
+/// Pay a stream's tokens to its recipient, counting them in `TotalWithdrawn`.
+fn pay_recipient(env: &Env, to: &Address, amount: i128) -> Result<(), ContractError> {
+    transfer_from_contract(env, to, amount)?;
+    add_to_total(env, DataKey::TotalWithdrawn, amount);
+    Ok(())
+}
+
+/// Return a stream's tokens to its sender, counting them in `TotalRefunded`.
+fn refund_sender(env: &Env, to: &Address, amount: i128) -> Result<(), ContractError> {
+    transfer_from_contract(env, to, amount)?;
+    add_to_total(env, DataKey::TotalRefunded, amount);
+    Ok(())
+}
+
+/// Pay `amount` of the stream token out of the contract.
+///
+/// Checks the contract's balance first so drifted accounting surfaces as
+/// `ContractError::InsufficientContractBalance` instead of an opaque token panic.
+/// Callers may already have saved state: the error aborts the invocation and rolls
+/// those writes back.
+fn transfer_from_contract(env: &Env, to: &Address, amount: i128) -> Result<(), ContractError> {
+    let token_client = token::Client::new(env, &get_token(env));
+    let contract = env.current_contract_address();
+    if token_client.balance(&contract) < amount {
+        return Err(ContractError::InsufficientContractBalance);
+    }
+    token_client.transfer(&contract, to, &amount);
+    Ok(())
+}
+
+/// Clear a stream's held security deposit, returning the amount to pay out. Every
+/// transition to `Completed` or `Cancelled` refunds it to the sender along with the
+/// rest of its refund.
+fn take_security_deposit(stream: &mut Stream) -> i128 {
+    core::mem::take(&mut stream.security_deposit)
+}
+
+/// Call `stream_received(stream_id, amount)` on a stream's withdraw hook.
+///
+/// Withdrawals are locked out while the hook runs. A failing or missing hook is
+/// ignored, so a recipient's hook can never block a payout (or a sender's
+/// `cancel_and_settle`) that has already happened.
+fn notify_withdraw_hook(env: &Env, hook: &Address, stream_id: u64, amount: i128) {
+    env.storage().temporary().set(&DataKey::WithdrawLock, &true);
+    let _ = env.try_invoke_contract::<(), soroban_sdk::Error>(
+        hook,
+        &Symbol::new(env, "stream_received"),
+        (stream_id, amount).into_val(env),
+    );
+    env.storage().temporary().remove(&DataKey::WithdrawLock);
+}
+
+/// Convert a `Stream` to its storage form.
+fn pack_stream(stream: &Stream) -> PackedStream {
+    let mut flags = stream.status as u32;
+    if stream.cancelled_at.is_some() {
+        flags |= HAS_CANCELLED_AT;
+    }
+    if stream.completed_at.is_some() {
+        flags |= HAS_COMPLETED_AT;
+    }
+    if stream.last_withdraw_time.is_some() {
+        flags |= HAS_LAST_WITHDRAW_TIME;
+    }
+    if stream.cancel_effective_at.is_some() {
+        flags |= HAS_CANCEL_EFFECTIVE_AT;
+    }
+    if stream.pause_requested {
+        flags |= PAUSE_REQUESTED;
+    }
+    if stream.paused_at.is_some() {
+        flags |= HAS_PAUSED_AT;
+    }
+
+    PackedStream(
+        stream.sender.clone(),
+        stream.recipient.clone(),
+        stream.deposit_amount,
+        stream.rate_per_second,
+        stream.start_time,
+        stream.cliff_time,
+        stream.end_time,
+        stream.withdrawn_amount,
+        flags,
+        stream.cancelled_at.unwrap_or(0),
+        stream.completed_at.unwrap_or(0),
+        stream.min_withdraw_interval,
+        stream.last_withdraw_time.unwrap_or(0),
+        stream.min_withdraw_amount,
+        stream.accrued_checkpoint,
+        stream.checkpoint_time,
+        stream.unclaimed_sweep_after,
+        stream.swept_amount,
+        stream.cliff_unlock_amount,
+        stream.accrual_kind.clone(),
+        stream.last_updated,
+        stream.cancel_effective_at.unwrap_or(0),
+        stream.max_withdrawable,
+        stream.category.clone(),
+        stream.paused_at.unwrap_or(0),
+        stream.on_withdraw_hook.clone(),
+        stream.security_deposit,
+        stream.created_at,
+    )
+}
+
+/// Rebuild the `Stream` with id `stream_id` from its storage form.
+fn unpack_stream(stream_id: u64, packed: PackedStream) -> Stream {
+    let flags = packed.8;
+    let status = match flags & STATUS_MASK {
+        0 => StreamStatus::Active,
+        1 => StreamStatus::Paused,
+        2 => StreamStatus::Completed,
+        3 => StreamStatus::Cancelled,
+        4 => StreamStatus::PendingAcceptance,
+        5 => StreamStatus::Unfunded,
+        6 => StreamStatus::Settled,
+        _ => panic!("invalid packed stream status"),
+    };
+    let optional = |flag: u32, value: u64| (flags & flag != 0).then_some(value);
+
+    Stream {
+        stream_id,
+        sender: packed.0,
+        recipient: packed.1,
+        deposit_amount: packed.2,
+        rate_per_second: packed.3,
+        start_time: packed.4,
+        cliff_time: packed.5,
+        end_time: packed.6,
+        withdrawn_amount: packed.7,
+        status,
+        cancelled_at: optional(HAS_CANCELLED_AT, packed.9),
+        completed_at: optional(HAS_COMPLETED_AT, packed.10),
+        min_withdraw_interval: packed.11,
+        last_withdraw_time: optional(HAS_LAST_WITHDRAW_TIME, packed.12),
+        min_withdraw_amount: packed.13,
+        accrued_checkpoint: packed.14,
+        checkpoint_time: packed.15,
+        unclaimed_sweep_after: packed.16,
+        swept_amount: packed.17,
+        cliff_unlock_amount: packed.18,
+        accrual_kind: packed.19,
+        last_updated: packed.20,
+        cancel_effective_at: optional(HAS_CANCEL_EFFECTIVE_AT, packed.21),
+        max_withdrawable: packed.22,
+        pause_requested: flags & PAUSE_REQUESTED != 0,
+        category: packed.23,
+        paused_at: optional(HAS_PAUSED_AT, packed.24),
+        on_withdraw_hook: packed.25,
+        security_deposit: packed.26,
+        created_at: packed.27,
+    }
+}
+
+/// A `LegacyStream` with every later field at the value the original release implied:
+/// linear accrual from `start_time`, the whole deposit withdrawable, no options set.
+/// Its creation time was not recorded, so `created_at` is taken as `start_time`.
+fn unpack_legacy_stream(legacy: LegacyStream) -> Stream {
+    Stream {
+        stream_id: legacy.stream_id,
+        sender: legacy.sender,
+        recipient: legacy.recipient,
+        deposit_amount: legacy.deposit_amount,
+        rate_per_second: legacy.rate_per_second,
+        start_time: legacy.start_time,
+        cliff_time: legacy.cliff_time,
+        end_time: legacy.end_time,
+        withdrawn_amount: legacy.withdrawn_amount,
+        status: legacy.status,
+        cancelled_at: legacy.cancelled_at,
+        completed_at: None,
+        min_withdraw_interval: 0,
+        last_withdraw_time: None,
+        min_withdraw_amount: 0,
+        accrued_checkpoint: 0,
+        checkpoint_time: legacy.start_time,
+        unclaimed_sweep_after: 0,
+        swept_amount: 0,
+        cliff_unlock_amount: 0,
+        accrual_kind: AccrualKind::Linear,
+        last_updated: 0,
+        cancel_effective_at: None,
+        max_withdrawable: legacy.deposit_amount,
+        pause_requested: false,
+        category: DEFAULT_CATEGORY,
+        paused_at: None,
+        on_withdraw_hook: None,
+        security_deposit: 0,
+        created_at: legacy.start_time,
+    }
+}
+
+fn load_stream(env: &Env, stream_id: u64) -> Result<Stream, ContractError> {
+    let storage = env.storage().persistent();
+    if let Some(packed) = storage.get(&DataKey::StreamV2(stream_id)) {
+        return Ok(unpack_stream(stream_id, packed));
+    }
+    storage
+        .get(&DataKey::Stream(stream_id))
+        .map(unpack_legacy_stream)
+        .ok_or(ContractError::StreamNotFound)
+}
+
+fn save_stream(env: &Env, stream: &Stream) {
+    let key = DataKey::StreamV2(stream.stream_id);
+    let legacy_key = DataKey::Stream(stream.stream_id);
+    let storage = env.storage().persistent();
+    // Status counters follow the stored status, so every transition is counted here.
+    let mut previous = storage
+        .get::<_, PackedStream>(&key)
+        .map(|packed| unpack_stream(stream.stream_id, packed).status);
+    if previous.is_none() {
+        // A stream written by the original release now moves to the new key.
+        previous = storage
+            .get::<_, LegacyStream>(&legacy_key)
+            .map(|legacy| legacy.status);
+        if previous.is_some() {
+            storage.remove(&legacy_key);
+        }
+    }
+    write_stream(env, stream, previous);
+}
+
+/// Store `stream`, moving the status counters from `previous` (`None` for a new stream).
+fn write_stream(env: &Env, stream: &Stream, previous: Option<StreamStatus>) {
+    move_status_count(env, previous, stream.status);
+
+    let key = DataKey::StreamV2(stream.stream_id);
+    let mut packed = pack_stream(stream);
+    // Every write stamps `last_updated`, so callers never have to.
+    packed.20 = env.ledger().timestamp();
+    env.storage().persistent().set(&key, &packed);
+
+    // Requirement from Issue #1: extend TTL on stream save to ensure persistence
+    env.storage().persistent().extend_ttl(&key, 17280, 120960);
+}
+
+fn get_category_streams(env: &Env, category: &Symbol) -> Vec<u64> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::CategoryStreams(category.clone()))
+        .unwrap_or(Vec::new(env))
+}
+
+/// Append `stream_id` to its category's index. Ids only ever go in, in creation order.
+fn index_stream_category(env: &Env, category: &Symbol, stream_id: u64) {
+    let key = DataKey::CategoryStreams(category.clone());
+    let mut ids = get_category_streams(env, category);
+    ids.push_back(stream_id);
+    env.storage().persistent().set(&key, &ids);
+    env.storage().persistent().extend_ttl(&key, 17280, 120960);
+}
+
+fn get_recipient_streams(env: &Env, recipient: &Address) -> Vec<u64> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::RecipientStreams(recipient.clone()))
+        .unwrap_or(Vec::new(env))
+}
+
+/// Append `stream_id` to its recipient's index. Ids go in in creation order, except
+/// that a stream re-pointed by `set_recipient_before_start` is appended to its new
+/// recipient's index at that time.
+fn index_stream_recipient(env: &Env, recipient: &Address, stream_id: u64) {
+    let key = DataKey::RecipientStreams(recipient.clone());
+    let mut ids = get_recipient_streams(env, recipient);
+    ids.push_back(stream_id);
+    env.storage().persistent().set(&key, &ids);
+    env.storage().persistent().extend_ttl(&key, 17280, 120960);
+}
+
+/// Drop `stream_id` from `recipient`'s index.
+fn unindex_stream_recipient(env: &Env, recipient: &Address, stream_id: u64) {
+    let key = DataKey::RecipientStreams(recipient.clone());
+    let mut ids = get_recipient_streams(env, recipient);
+    if let Some(index) = ids.first_index_of(stream_id) {
+        ids.remove(index);
+        env.storage().persistent().set(&key, &ids);
+        env.storage().persistent().extend_ttl(&key, 17280, 120960);
+    }
+}
+
+fn get_sender_streams(env: &Env, sender: &Address) -> Vec<u64> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::SenderStreams(sender.clone()))
+        .unwrap_or(Vec::new(env))
+}
+
+/// Append `stream_id` to its sender's index. Ids go in in creation order, except that
+/// a stream handed over by `transfer_sender` is appended to its new sender's index at
+/// that time.
+fn index_stream_sender(env: &Env, sender: &Address, stream_id: u64) {
+    let key = DataKey::SenderStreams(sender.clone());
+    let mut ids = get_sender_streams(env, sender);
+    ids.push_back(stream_id);
+    env.storage().persistent().set(&key, &ids);
+    env.storage().persistent().extend_ttl(&key, 17280, 120960);
+}
+
+/// Drop `stream_id` from `sender`'s index.
+fn unindex_stream_sender(env: &Env, sender: &Address, stream_id: u64) {
+    let key = DataKey::SenderStreams(sender.clone());
+    let mut ids = get_sender_streams(env, sender);
+    if let Some(index) = ids.first_index_of(stream_id) {
+        ids.remove(index);
+        env.storage().persistent().set(&key, &ids);
+        env.storage().persistent().extend_ttl(&key, 17280, 120960);
+    }
+}
+
+fn get_withdraw_log(env: &Env, stream_id: u64) -> Vec<(u64, i128)> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::WithdrawLog(stream_id))
+        .unwrap_or(Vec::new(env))
+}
+
+/// Append a `(timestamp, amount)` withdrawal to the stream's log, dropping the oldest
+/// entry once the log holds `MAX_WITHDRAW_LOG` entries.
+fn log_withdrawal(env: &Env, stream_id: u64, timestamp: u64, amount: i128) {
+    let key = DataKey::WithdrawLog(stream_id);
+    let mut log = get_withdraw_log(env, stream_id);
+    if log.len() >= MAX_WITHDRAW_LOG {
+        log.pop_front();
+    }
+    log.push_back((timestamp, amount));
+    env.storage().persistent().set(&key, &log);
+    env.storage().persistent().extend_ttl(&key, 17280, 120960);
+}
+
+// ---------------------------------------------------------------------------
+// Contract Implementation
+// ---------------------------------------------------------------------------
+
+#[contract]
+pub struct FluxoraStream;
+
+#[contractimpl]
+impl FluxoraStream {
+    /// Initialise the contract with the streaming token and admin address.
+    ///
+    /// This function must be called exactly once before any other contract operations.
+    /// It persists the token address (used for all stream transfers) and admin address
+    /// (authorized for administrative operations) in instance storage.
+    ///
+    /// # Parameters
+    /// - `token`: Address of the token contract used for all payment streams
+    /// - `admin`: Address authorized to perform administrative operations (pause, cancel, etc.)
+    ///
+    /// # Storage
+    /// - Stores `Config { token, admin }` in instance storage under `DataKey::ConfigV2`, with
+    ///   no creation fee and `admin` as the fee collector
+    /// - Initializes `NextStreamId` counter to 0 for stream ID generation
+    /// - Extends TTL to prevent premature expiration (17280 ledgers threshold, 120960 max)
+    ///
+    /// # Panics
+    /// - If called more than once (contract already initialized)
+    /// - With `ContractError::InvalidToken` if `token` does not answer `decimals()`
+    ///   (not a deployed token contract), so misconfiguration fails at deploy time
+    ///   rather than on the first transfer
+    ///
+    /// # Security
+    /// - Re-initialization is prevented; the token and admin change only through
+    ///   `set_token` and `set_admin`
+    /// - No authorization required for initial setup (deployer calls this once)
+    pub fn init(env: Env, token: Address, admin: Address) {
+        if try_get_config(&env).is_some() {
+            panic!("already initialised");
+        }
+        let probe = token::Client::new(&env, &token).try_decimals();
+        if !matches!(probe, Ok(Ok(_))) {
+            panic_with_error!(&env, ContractError::InvalidToken);
+        }
+        set_config(&env, &default_config(token, admin));
+        env.storage().instance().set(&DataKey::NextStreamId, &0u64);
+
+        // Ensure instance storage (Config/ID) doesn't expire quickly
+        env.storage().instance().extend_ttl(17280, 120960);
+    }
+
+    /// Initialise the contract to stream native XLM.
+    ///
+    /// Same as `init` with the native asset's Stellar Asset Contract as the token. Its
+    /// address is derived from the network id, so deployers do not have to look it up.
+    /// Every transfer goes through `token::Client`, so native streams need nothing else.
+    ///
+    /// # Panics
+    /// - Same as `init`; `ContractError::InvalidToken` if the native asset contract has
+    ///   not been deployed on this network
+    pub fn init_native(env: Env, admin: Address) {
+        let token = env
+            .deployer()
+            .with_stellar_asset(Bytes::from_array(&env, &NATIVE_ASSET_XDR))
+            .deployed_address();
+        Self::init(env, token, admin);
+    }
+
+    /// Create a new payment stream with specified parameters.
+    ///
+    /// Establishes a new token stream from sender to recipient with defined rate and duration.
+    /// Transfers the deposit amount from sender to the contract immediately. Returns a unique
+    /// stream ID that can be used to interact with the stream.
+    ///
+    /// # Parameters
+    /// - `sender`: Address funding the stream (must authorize the transaction)
+    /// - `recipient`: Address receiving the streamed tokens
+    /// - `deposit_amount`: Total tokens to deposit (must be > 0)
+    /// - `rate_per_second`: Streaming rate in tokens per second (must be > 0)
+    /// - `start_time`: When streaming begins (ledger timestamp). A `start_time` after
+    ///   the creation time locks the deposit now and starts accrual later; until then
+    ///   nothing accrues and the sender can only get the deposit back by cancelling
+    /// - `cliff_time`: When tokens first become available (vesting cliff, must be in [start_time, end_time])
+    /// - `end_time`: When streaming completes (must be > start_time)
+    ///
+    /// # Returns
+    /// - `u64`: Unique stream identifier for the newly created stream
+    ///
+    /// # Authorization
+    /// - Requires authorization from the sender address
+    ///
+    /// # Validation
+    /// The function validates all parameters before creating the stream:
+    /// - `deposit_amount > 0` and `rate_per_second > 0`
+    /// - `sender != recipient` (cannot stream to yourself)
+    /// - `start_time < end_time` (valid time range)
+    /// - `cliff_time` in `[start_time, end_time]` (cliff within stream duration)
+    /// - `deposit_amount >= rate_per_second × (end_time - start_time)` (sufficient deposit)
+    ///
+    /// # Panics
+    /// - If `deposit_amount` or `rate_per_second` is not positive
+    /// - If `sender` and `recipient` are the same address
+    /// - If `start_time >= end_time` (invalid time range)
+    /// - If `cliff_time` is not in `[start_time, end_time]`
+    /// - If `deposit_amount < rate_per_second × (end_time - start_time)` (insufficient deposit)
+    /// - If the stream token is not whitelisted (`ContractError::TokenNotAllowed`)
+    /// - If the creator allowlist is enabled and `sender` is not on it
+    ///   (`ContractError::CreatorNotAllowed`)
+    /// - If the admin has paused deposits (`ContractError::DepositsPaused`)
+    /// - If the deposit is above the admin-set `max_deposit` (`ContractError::DepositExceedsCap`)
+    /// - If `end_time - start_time` is below the admin-set `min_duration_seconds`
+    ///   (`ContractError::DurationTooShort`)
+    /// - If `sender` cancelled a stream within the admin-set `cancel_cooldown_seconds`
+    ///   (`ContractError::CancelCooldown`)
+    /// - If `start_time` is further in the past than the admin-set `max_backdating`
+    ///   (`ContractError::StartTooFarInPast`)
+    /// - If token transfer fails (insufficient balance or allowance)
+    /// - If overflow occurs calculating total streamable amount
+    ///
+    /// # State Changes
+    /// - Transfers `deposit_amount` tokens from sender to contract
+    /// - Creates new stream with status `Active`
+    /// - Increments global stream counter
+    /// - Stores stream data in persistent storage with extended TTL
+    ///
+    /// # Events
+    /// - Publishes `created(sender, recipient, (stream_id, deposit_amount))` event on success
+    ///
+    /// # Usage Notes
+    /// - Transaction is atomic: if token transfer fails, no stream is created
+    /// - Stream IDs are sequential starting from 0
+    /// - Cliff time enables vesting schedules (no withdrawals before cliff)
+    /// - Setting `cliff_time = start_time` means no cliff (immediate vesting)
+    /// - Deposit can exceed minimum required (excess remains in contract)
+    /// - Sender must have sufficient token balance and approve contract
+    /// ## Stream Limits Policy
+    /// There is no fixed upper bound on `deposit_amount` or stream duration: overflow
+    /// in accrual math is already prevented via `checked_mul` and clamping, and the
+    /// overflow test suite exercises values up to `i128::MAX`. Beyond the validations
+    /// above, the admin can opt into the following creation limits, each off by
+    /// default:
+    /// - `set_max_deposit`: per-stream deposit cap (`DepositExceedsCap`)
+    /// - `set_min_duration`: shortest allowed `end_time - start_time` (`DurationTooShort`)
+    /// - `set_max_backdating`: how far in the past `start_time` may lie
+    ///   (`StartTooFarInPast`)
+    /// - `set_cancel_cooldown`: wait imposed on a sender after cancelling a stream
+    ///   (`CancelCooldown`)
+    /// - `set_creators_allowlist`: only allowlisted senders may create (`CreatorNotAllowed`)
+    /// - `add_allowed_token`: token whitelist (`TokenNotAllowed`)
+    /// - `set_deposits_paused`: freeze all new deposits (`DepositsPaused`)
+    ///
+    /// Finer business rules (e.g. "max 10 M USDC per recipient") belong at the
+    /// application layer, where that context is available. Senders remain responsible
+    /// for the correctness of the values they supply.
+    ///
+    /// # Examples
+    /// - Linear stream: 1000 tokens over 1000 seconds, no cliff
+    ///   - `deposit_amount = 1000`, `rate = 1`, `start = 0`, `cliff = 0`, `end = 1000`
+    /// - Vesting stream: 12000 tokens over 12 months, 6-month cliff
+    ///   - `deposit_amount = 12000`, `rate = 1`, `start = 0`, `cliff = 15552000`, `end = 31104000`
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_stream(
+        env: Env,
+        sender: Address,
+        recipient: Address,
+        deposit_amount: i128,
+        rate_per_second: i128,
+        start_time: u64,
+        cliff_time: u64,
+        end_time: u64,
+    ) -> u64 {
+        Self::open_stream(
+            &env,
+            sender.clone(),
+            sender,
+            recipient,
+            deposit_amount,
+            rate_per_second,
+            start_time,
+            cliff_time,
+            end_time,
+            CreateStreamOptions::default(),
+            None,
+        )
+    }
+
+    /// Create several payment streams from one sender in a single transaction.
+    ///
+    /// Every entry is validated exactly like `create_stream`, then the summed deposit
+    /// is pulled from the sender and the streams are stored in order. The batch is
+    /// atomic: if any entry is invalid, nothing is created.
+    ///
+    /// The running total is kept in `I256`, so a batch whose deposits sum past
+    /// `i128::MAX` is still valid. It is pulled with as few transfers as possible,
+    /// each at most `i128::MAX` (the largest amount a token transfer accepts).
+    ///
+    /// # Parameters
+    /// - `sender`: Address funding every stream in the batch (must authorize)
+    /// - `streams`: Per-stream parameters (recipient, deposit, rate, schedule)
+    ///
+    /// # Returns
+    /// - `Vec<u64>`: Stream IDs in the same order as `streams`
+    ///
+    /// # Authorization
+    /// - Requires authorization from the sender address
+    ///
+    /// # Panics
+    /// - `ContractError::EmptyBatch` if `streams` is empty
+    /// - `ContractError::TokenNotAllowed` if the stream token is not whitelisted
+    /// - `ContractError::CreatorNotAllowed` if the creator allowlist is enabled and
+    ///   `sender` is not on it
+    /// - `ContractError::DepositsPaused` if the admin has paused deposits
+    /// - `ContractError::BatchItemInvalid` if an entry fails any `create_stream`
+    ///   validation (including the backdating limit)
+    /// - If token transfer fails (insufficient balance or allowance)
+    ///
+    /// # Events
+    /// - Publishes one `created(sender, recipient, (stream_id, deposit_amount))` event per stream
+    /// - On `BatchItemInvalid`, publishes `batch_err(sender)` with
+    ///   `StreamEvent::BatchItemRejected { index, error }` right before the revert
+    pub fn create_streams(env: Env, sender: Address, streams: Vec<CreateStreamParams>) -> Vec<u64> {
+        sender.require_auth();
+        require_creator_permitted(&env, &sender);
+        require_deposits_open(&env);
+
+        if streams.is_empty() {
+            panic_with_error!(&env, ContractError::EmptyBatch);
+        }
+
+        let mut total_deposit = I256::from_i128(&env, 0);
+        let mut total_fee = I256::from_i128(&env, 0);
+        let mut net_deposits = Vec::new(&env);
+        for (index, params) in streams.iter().enumerate() {
+            let (fee, deposit_amount) = split_creation_fee(&env, params.deposit_amount);
+            let checked = Self::check_stream_params(
+                &env,
+                &sender,
+                &params.recipient,
+                deposit_amount,
+                params.rate_per_second,
+                params.start_time,
+                params.cliff_time,
+                params.end_time,
+                &AccrualKind::Linear,
+            );
+            if let Err(invalid) = checked {
+                reject_batch_item(&env, &sender, index as u32, invalid.into_error());
+            }
+            if is_start_too_far_in_past(&env, params.start_time) {
+                reject_batch_item(
+                    &env,
+                    &sender,
+                    index as u32,
+                    ContractError::StartTooFarInPast,
+                );
+            }
+            total_deposit = total_deposit.add(&I256::from_i128(&env, deposit_amount));
+            total_fee = total_fee.add(&I256::from_i128(&env, fee));
+            net_deposits.push_back(deposit_amount);
+        }
+
+        let token = get_token(&env);
+        require_token_allowed(&env, &token);
+
+        let token_client = token::Client::new(&env, &token);
+        Self::transfer_i256(
+            &env,
+            &token_client,
+            &sender,
+            &env.current_contract_address(),
+            total_deposit,
+        );
+        Self::transfer_i256(
+            &env,
+            &token_client,
+            &sender,
+            &get_config(&env).fee_collector,
+            total_fee,
+        );
+
+        let mut stream_ids = Vec::new(&env);
+        for (params, deposit_amount) in streams.iter().zip(net_deposits.iter()) {
+            let stream_id = Self::store_new_stream(
+                &env,
+                sender.clone(),
+                params.recipient,
+                deposit_amount,
+                params.rate_per_second,
+                params.start_time,
+                params.cliff_time,
+                params.end_time,
+                &CreateStreamOptions::default(),
+                StreamStatus::Active,
+            );
+            stream_ids.push_back(stream_id);
+        }
+        stream_ids
+    }
+
+    /// Create several payment streams like `create_streams`, returning a receipt per
+    /// stream.
+    ///
+    /// Each receipt carries the stream id, its `total_streamable` amount, and the
+    /// `excess` of its stored deposit (net of any creation fee) over that amount, so a
+    /// treasury can reconcile a large batch without reading every stream back.
+    ///
+    /// # Parameters
+    /// - `sender`: Address funding every stream in the batch (must authorize)
+    /// - `streams`: Per-stream parameters (recipient, deposit, rate, schedule)
+    ///
+    /// # Returns
+    /// - `Vec<CreateStreamReceipt>`: One receipt per entry, in the same order as `streams`
+    ///
+    /// # Authorization
+    /// - Requires authorization from the sender address
+    ///
+    /// # Panics
+    /// - Under the same conditions as `create_streams`
+    ///
+    /// # Events
+    /// - Same as `create_streams`
+    pub fn create_streams_with_receipts(
+        env: Env,
+        sender: Address,
+        streams: Vec<CreateStreamParams>,
+    ) -> Vec<CreateStreamReceipt> {
+        let stream_ids = Self::create_streams(env.clone(), sender, streams);
+
+        let mut receipts = Vec::new(&env);
+        for stream_id in stream_ids.iter() {
+            let stream = load_stream(&env, stream_id).expect("created stream exists");
+            // Validation already checked this product fits and is covered by the deposit.
+            let total_streamable = curve_total_streamable(
+                &stream.accrual_kind,
+                stream.rate_per_second,
+                stream.start_time,
+                stream.end_time,
+            )
+            .expect("overflow calculating total streamable amount");
+            receipts.push_back(CreateStreamReceipt {
+                stream_id,
+                total_streamable,
+                excess: stream.deposit_amount - total_streamable,
+            });
+        }
+        receipts
+    }
+
+    /// Create a new payment stream with optional creation settings.
+    ///
+    /// Same parameters, validation, transfer, and events as `create_stream`, plus
+    /// `options` for behaviour that plain `create_stream` leaves at its defaults.
+    ///
+    /// # Options
+    /// - `requires_acceptance`: the stream starts in `PendingAcceptance` and accrues
+    ///   nothing until the recipient calls `accept_stream`, which restarts the schedule
+    ///   at the acceptance time. The recipient may instead call `reject_stream` to
+    ///   refund the sender in full. Protects recipients from unsolicited streams.
+    /// - `min_withdraw_interval`: minimum seconds between withdrawals. A withdrawal
+    ///   earlier than `last_withdraw_time + min_withdraw_interval` fails with
+    ///   `ContractError::WithdrawTooSoon`; the first withdrawal is always allowed.
+    ///   Throttles bots that would otherwise claim dust every ledger.
+    /// - `min_withdraw_amount`: smallest amount a withdrawal may transfer. A withdrawal
+    ///   of a positive amount below it fails with `ContractError::BelowMinWithdraw`,
+    ///   except once the stream has ended or been cancelled, so the final remainder
+    ///   can always be claimed. Must not be negative.
+    /// - `unclaimed_sweep_after`: seconds after `end_time` from which the sender may call
+    ///   `sweep_unclaimed` to take back accrual the recipient never withdrew. `0`
+    ///   disables sweeping.
+    /// - `accrual_kind`: release curve. `AccrualKind::FrontLoaded` halves the release
+    ///   rate every `halflife_seconds` (which must be positive) and rescales the curve so
+    ///   the full deposit is released exactly at `end_time`; `rate_per_second` then only
+    ///   takes part in the deposit validation. `AccrualKind::Scheduled` accrues
+    ///   `rate_per_second` only during the UTC hours of the week its mask enables
+    ///   (at least one, with no bits outside the week), and the deposit then only has
+    ///   to cover `rate × active seconds` between `start_time` and `end_time`.
+    /// - `cliff_unlock_amount`: amount released in one step when the cliff is reached,
+    ///   added to the linear accrual and capped at `deposit_amount`. Must be within
+    ///   `[0, deposit_amount]`; a deposit of exactly `rate × duration` then fills
+    ///   before `end_time`, so fund `cliff_unlock_amount + rate × duration` to keep the
+    ///   linear part running to the end.
+    /// - `auto_extend`: a deposit (net of any creation fee) above `rate × duration`
+    ///   stretches the schedule instead of sitting idle: `end_time` becomes
+    ///   `start_time + deposit / rate`. Validation uses the `end_time` passed in, and
+    ///   `cliff_time` is kept. A remainder of `deposit % rate` is never streamed, as
+    ///   with any deposit above `rate × duration`. `Linear` streams only.
+    /// - `security_deposit`: extra amount pulled in with the deposit (so the transfer is
+    ///   `deposit_amount + security_deposit`) and held outside accrual. It is refunded to
+    ///   the sender with the rest of the refund when the stream completes or is
+    ///   cancelled, unless the admin pays it to the recipient first with
+    ///   `forfeit_deposit_as_admin`. Must not be negative; no creation fee is charged
+    ///   on it.
+    ///
+    /// # Returns
+    /// - `u64`: Unique stream identifier for the newly created stream
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_stream_with_options(
+        env: Env,
+        sender: Address,
+        recipient: Address,
+        deposit_amount: i128,
+        rate_per_second: i128,
+        start_time: u64,
+        cliff_time: u64,
+        end_time: u64,
+        options: CreateStreamOptions,
+    ) -> u64 {
+        Self::open_stream(
+            &env,
+            sender.clone(),
+            sender,
+            recipient,
+            deposit_amount,
+            rate_per_second,
+            start_time,
+            cliff_time,
+            end_time,
+            options,
+            None,
+        )
+    }
+
+    /// Create a stream that a retried submission cannot duplicate.
+    ///
+    /// Same parameters, validation, transfer, and events as `create_stream`, plus a
+    /// client-chosen `idempotency_key`. The first call with a key creates the stream and
+    /// records its id; a repeat from the same sender returns that id without
+    /// validating, transferring, or emitting anything, so a frontend can safely resubmit
+    /// a create whose outcome it never saw. Keys are recorded per sender, so another
+    /// sender cannot claim a key first, and the id is returned even once the stream
+    /// itself is gone from storage.
+    ///
+    /// # Parameters
+    /// - `idempotency_key`: Unique per intended stream of the sender, e.g. a hash of the
+    ///   form submission; other parameters as for `create_stream`
+    ///
+    /// # Panics
+    /// - Under the same conditions as `create_stream` (first call only)
+    ///
+    /// # Returns
+    /// - `u64`: The new stream's id, or the id first created under the key
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_stream_idempotent(
+        env: Env,
+        sender: Address,
+        recipient: Address,
+        deposit_amount: i128,
+        rate_per_second: i128,
+        start_time: u64,
+        cliff_time: u64,
+        end_time: u64,
+        idempotency_key: BytesN<32>,
+    ) -> u64 {
+        let key = DataKey::IdemKey(sender.clone(), idempotency_key);
+        if let Some(stream_id) = env.storage().persistent().get::<_, u64>(&key) {
+            return stream_id;
+        }
+
+        let stream_id = Self::open_stream(
+            &env,
+            sender.clone(),
+            sender,
+            recipient,
+            deposit_amount,
+            rate_per_second,
+            start_time,
+            cliff_time,
+            end_time,
+            CreateStreamOptions::default(),
+            None,
+        );
+        env.storage().persistent().set(&key, &stream_id);
+        env.storage().persistent().extend_ttl(&key, 17280, 120960);
+        stream_id
+    }
+
+    /// Create a stream whose deposit is paid by a separate `payer`.
+    ///
+    /// Same parameters, validation, and events as `create_stream`, except that the
+    /// deposit is pulled from `payer` while `sender` is recorded as the stream owner.
+    /// The payer has no rights over the stream afterwards: pausing, resuming,
+    /// cancelling, and top-ups are the sender's, and cancellation refunds go to the
+    /// sender. Lets a treasury fund streams managed by an operator.
+    ///
+    /// # Authorization
+    /// - Requires authorization from both `sender` and `payer`
+    ///
+    /// # Panics
+    /// - If `payer`'s token balance is below `deposit_amount`
+    /// - Under the same conditions as `create_stream`
+    ///
+    /// # Returns
+    /// - `u64`: Unique stream identifier for the newly created stream
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_stream_funded_by(
+        env: Env,
+        sender: Address,
+        payer: Address,
+        recipient: Address,
+        deposit_amount: i128,
+        rate_per_second: i128,
+        start_time: u64,
+        cliff_time: u64,
+        end_time: u64,
+    ) -> u64 {
+        let token_client = token::Client::new(&env, &get_token(&env));
+        assert!(
+            token_client.balance(&payer) >= deposit_amount,
+            "payer balance must cover deposit_amount"
+        );
+
+        Self::open_stream(
+            &env,
+            payer,
+            sender,
+            recipient,
+            deposit_amount,
+            rate_per_second,
+            start_time,
+            cliff_time,
+            end_time,
+            CreateStreamOptions::default(),
+            None,
+        )
+    }
+
+    /// Create a stream from an amount per period instead of a per-second rate.
+    ///
+    /// Salaries and grants are usually quoted per month or week; this derives
+    /// `rate_per_second = amount_per_period / period_seconds` and stores it on the
+    /// stream. A quotient that does not come out even is rounded per the configured
+    /// `rounding_mode`: `Floor` (the default) leaves the dust with the sender, so a
+    /// full period pays slightly less than `amount_per_period`; `Ceil` pays slightly
+    /// more and needs the deposit to cover the rounded-up rate.
+    ///
+    /// # Parameters
+    /// - `amount_per_period`: Tokens released per period (must be > 0)
+    /// - `period_seconds`: Length of a period, e.g. `2_592_000` for 30 days (must be > 0)
+    /// - Other parameters as for `create_stream`
+    ///
+    /// # Panics
+    /// - If `amount_per_period` or `period_seconds` is not positive
+    /// - If the rounded rate is zero (`"rate_per_second must be positive"`)
+    /// - Under the same conditions as `create_stream`
+    ///
+    /// # Returns
+    /// - `u64`: Unique stream identifier for the newly created stream
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_stream_rate_per_period(
+        env: Env,
+        sender: Address,
+        recipient: Address,
+        deposit_amount: i128,
+        amount_per_period: i128,
+        period_seconds: u64,
+        start_time: u64,
+        cliff_time: u64,
+        end_time: u64,
+    ) -> u64 {
+        assert!(amount_per_period > 0, "amount_per_period must be positive");
+        assert!(period_seconds > 0, "period_seconds must be positive");
+        let rate_per_second = div_rounded(
+            amount_per_period,
+            i128::from(period_seconds),
+            get_config(&env).rounding_mode,
+        );
+
+        Self::create_stream(
+            env,
+            sender,
+            recipient,
+            deposit_amount,
+            rate_per_second,
+            start_time,
+            cliff_time,
+            end_time,
+        )
+    }
+
+    /// Create a stream from a total amount and a duration instead of a per-second rate.
+    ///
+    /// "Stream 1000 tokens over 30 days" becomes a stream with `deposit_amount =
+    /// total_amount`, `end_time = start_time + duration_seconds`, and the derived
+    /// `rate_per_second = total_amount / duration_seconds` stored on it. When the
+    /// division leaves a remainder, `allow_remainder` decides what happens: `true` keeps
+    /// it in the deposit as a non-streamable buffer (`max_withdrawable = rate ×
+    /// duration`) that is returned to the sender when the stream completes or is
+    /// cancelled; `false` rejects the amount so the recipient gets exactly
+    /// `total_amount`.
+    ///
+    /// # Parameters
+    /// - `total_amount`: Tokens to deposit and stream (must be > 0)
+    /// - `duration_seconds`: Length of the schedule from `start_time` (must be > 0)
+    /// - `allow_remainder`: Accept a `total_amount` that does not divide evenly
+    /// - Other parameters as for `create_stream`
+    ///
+    /// # Panics
+    /// - If `total_amount` or `duration_seconds` is not positive
+    /// - If `total_amount % duration_seconds != 0` and `allow_remainder` is `false`
+    /// - If `start_time + duration_seconds` overflows `u64`
+    /// - If the derived rate is zero (`"rate_per_second must be positive"`)
+    /// - Under the same conditions as `create_stream`; with a `creation_fee_bps` set,
+    ///   the deposit left after the fee no longer covers `rate × duration`
+    ///
+    /// # Returns
+    /// - `u64`: Unique stream identifier for the newly created stream
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_stream_by_amount(
+        env: Env,
+        sender: Address,
+        recipient: Address,
+        total_amount: i128,
+        duration_seconds: u64,
+        start_time: u64,
+        cliff_time: u64,
+        allow_remainder: bool,
+    ) -> u64 {
+        assert!(total_amount > 0, "total_amount must be positive");
+        assert!(duration_seconds > 0, "duration_seconds must be positive");
+        let duration = i128::from(duration_seconds);
+        let rate_per_second = total_amount / duration;
+        let remainder = total_amount % duration;
+        assert!(
+            remainder == 0 || allow_remainder,
+            "total_amount must divide evenly by duration_seconds"
+        );
+        let end_time = start_time
+            .checked_add(duration_seconds)
+            .expect("overflow calculating end_time");
+
+        Self::open_stream(
+            &env,
+            sender.clone(),
+            sender,
+            recipient,
+            total_amount,
+            rate_per_second,
+            start_time,
+            cliff_time,
+            end_time,
+            CreateStreamOptions {
+                max_withdrawable: total_amount - remainder,
+                ..CreateStreamOptions::default()
+            },
+            None,
+        )
+    }
+
+    /// Check `create_stream` parameters without creating anything.
+    ///
+    /// Runs the same validation as `create_stream` against the current configuration
+    /// (creator allowlist, creation fee, deposit cap, minimum duration, backdating
+    /// limit, cancel cooldown, token whitelist) so a wallet can catch a rejection
+    /// before asking the user to sign a transaction that moves funds. No authorization
+    /// is required, nothing is transferred, and no state changes. The sender's balance
+    /// and allowance are not checked.
+    ///
+    /// # Parameters
+    /// Same as `create_stream`.
+    ///
+    /// # Errors
+    /// - The contract error `create_stream` would fail with (`CreatorNotAllowed`,
+    ///   `DepositsPaused`, `DepositExceedsCap`, `InvalidRecipient`, `DurationTooShort`,
+    ///   `CancelCooldown`, `StartTooFarInPast`, `TokenNotAllowed`)
+    /// - `ContractError::InvalidParams` where `create_stream` panics with a message
+    ///   instead (non-positive amounts, bad time range or cliff, under-funded deposit)
+    ///
+    /// # Usage Notes
+    /// - This is a view function (read-only, no state changes)
+    #[allow(clippy::too_many_arguments)]
+    pub fn validate_create(
+        env: Env,
+        sender: Address,
+        recipient: Address,
+        deposit_amount: i128,
+        rate_per_second: i128,
+        start_time: u64,
+        cliff_time: u64,
+        end_time: u64,
+    ) -> Result<(), ContractError> {
+        if !is_creator_permitted(&env, &sender) {
+            return Err(ContractError::CreatorNotAllowed);
+        }
+        if get_config(&env).deposits_paused {
+            return Err(ContractError::DepositsPaused);
+        }
+        let (_, deposit_amount) = split_creation_fee(&env, deposit_amount);
+        Self::check_stream_params(
+            &env,
+            &sender,
+            &recipient,
+            deposit_amount,
+            rate_per_second,
+            start_time,
+            cliff_time,
+            end_time,
+            &AccrualKind::Linear,
+        )
+        .map_err(InvalidParams::into_error)?;
+        if is_start_too_far_in_past(&env, start_time) {
+            return Err(ContractError::StartTooFarInPast);
+        }
+        if !is_stream_token_allowed(&env, &get_token(&env)) {
+            return Err(ContractError::TokenNotAllowed);
+        }
+        Ok(())
+    }
+
+    /// Record a payment stream now and fund it later with `fund_stream`.
+    ///
+    /// Validates exactly like `create_stream` but transfers nothing: the stream is stored
+    /// as `Unfunded` and accrues nothing, and the recipient cannot withdraw from it, until
+    /// the sender funds it. Lets a sender set up streams before the tokens (or their
+    /// allowance) are in place. The sender may cancel an unfunded stream, which refunds
+    /// nothing since nothing was deposited.
+    ///
+    /// # Parameters
+    /// Same as `create_stream`. `deposit_amount` is the gross amount `fund_stream` will
+    /// pull; the creation fee is taken from it at funding time.
+    ///
+    /// # Returns
+    /// - `u64`: Unique stream identifier for the newly created stream
+    ///
+    /// # Authorization
+    /// - Requires authorization from the sender address
+    ///
+    /// # Panics
+    /// - Under the same conditions as `create_stream`, except that no token transfer
+    ///   happens
+    ///
+    /// # Events
+    /// - Publishes `created(sender, recipient, (stream_id, deposit_amount))` event on success
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_unfunded_stream(
+        env: Env,
+        sender: Address,
+        recipient: Address,
+        deposit_amount: i128,
+        rate_per_second: i128,
+        start_time: u64,
+        cliff_time: u64,
+        end_time: u64,
+    ) -> u64 {
+        sender.require_auth();
+        require_creator_permitted(&env, &sender);
+
+        let (_, net_deposit) = split_creation_fee(&env, deposit_amount);
+        Self::validate_stream_params(
+            &env,
+            &sender,
+            &recipient,
+            net_deposit,
+            rate_per_second,
+            start_time,
+            cliff_time,
+            end_time,
+            &AccrualKind::Linear,
+        );
+        require_start_not_too_far_in_past(&env, start_time);
+        require_token_allowed(&env, &get_token(&env));
+
+        Self::store_new_stream(
+            &env,
+            sender,
+            recipient,
+            deposit_amount,
+            rate_per_second,
+            start_time,
+            cliff_time,
+            end_time,
+            &CreateStreamOptions::default(),
+            StreamStatus::Unfunded,
+        )
+    }
+
+    /// Fund a stream created with `create_unfunded_stream`, making it `Active`.
+    ///
+    /// Pulls the deposit from the sender and flips the stream to `Active`. Its schedule
+    /// is unchanged, so funding after `start_time` makes the time already elapsed
+    /// claimable at once. The deposit is re-validated against the current configuration
+    /// first: the creation fee is split off at today's `creation_fee_bps`, and the net
+    /// deposit must still cover the schedule and respect `max_deposit` and the token
+    /// whitelist.
+    ///
+    /// # Parameters
+    /// - `stream_id`: Unique identifier of the stream to fund
+    ///
+    /// # Returns
+    /// - `i128`: The net deposit now held for the stream
+    ///
+    /// # Authorization
+    /// - Requires authorization from the stream's sender, who pays the deposit
+    ///
+    /// # Errors
+    /// - `ContractError::StreamNotFound` if the stream does not exist
+    /// - `ContractError::InvalidState` if the stream is not `Unfunded`
+    /// - `ContractError::DepositsPaused` if the admin has paused deposits
+    ///
+    /// # Panics
+    /// - If the net deposit no longer covers `rate_per_second × (end_time - start_time)`
+    /// - If the stream token is not whitelisted (`ContractError::TokenNotAllowed`)
+    /// - If the deposit is above `max_deposit` (`ContractError::DepositExceedsCap`)
+    /// - If the token transfer fails (insufficient sender balance or allowance)
+    ///
+    /// # Events
+    /// - Publishes `funded(stream_id, deposit_amount)` event on success
+    pub fn fund_stream(env: Env, stream_id: u64) -> Result<i128, ContractError> {
+        let mut stream = load_stream(&env, stream_id)?;
+        Self::require_party_auth(&env, &stream.sender)?;
+
+        if stream.status != StreamStatus::Unfunded {
+            return Err(ContractError::InvalidState);
+        }
+        if get_config(&env).deposits_paused {
+            return Err(ContractError::DepositsPaused);
+        }
+
+        let (fee, deposit_amount) = split_creation_fee(&env, stream.deposit_amount);
+        Self::validate_stream_params(
+            &env,
+            &stream.sender,
+            &stream.recipient,
+            deposit_amount,
+            stream.rate_per_second,
+            stream.start_time,
+            stream.cliff_time,
+            stream.end_time,
+            &stream.accrual_kind,
+        );
+        let token = get_token(&env);
+        require_token_allowed(&env, &token);
+
+        // CEI: record the funded state before the external token transfers.
+        stream.deposit_amount = deposit_amount;
+        stream.max_withdrawable = deposit_amount;
+        stream.status = StreamStatus::Active;
+        save_stream(&env, &stream);
+        add_to_total(&env, DataKey::TotalDeposited, deposit_amount);
+
+        let token_client = token::Client::new(&env, &token);
+        token_client.transfer(
+            &stream.sender,
+            &env.current_contract_address(),
+            &deposit_amount,
+        );
+        if fee > 0 {
+            token_client.transfer(&stream.sender, &get_config(&env).fee_collector, &fee);
+        }
+
+        env.events()
+            .publish((symbol_short!("funded"), stream_id), deposit_amount);
+        Ok(deposit_amount)
+    }
+
+    /// Accept a stream that was created with `requires_acceptance`.
+    ///
+    /// Moves the stream from `PendingAcceptance` to `Active`. The schedule restarts at
+    /// the acceptance timestamp: `start_time` becomes `now`, and `cliff_time` and
+    /// `end_time` keep their original offsets from the start, so the duration, rate,
+    /// and required deposit are unchanged.
+    ///
+    /// # Parameters
+    /// - `stream_id`: Unique identifier of the stream to accept
+    ///
+    /// # Authorization
+    /// - Requires authorization from the stream's recipient
+    ///
+    /// # Errors
+    /// - `ContractError::StreamNotFound` if the stream does not exist
+    /// - `ContractError::InvalidState` if the stream is not `PendingAcceptance`
+    ///
+    /// # Events
+    /// - Publishes `Accepted(stream_id)` event on success
+    pub fn accept_stream(env: Env, stream_id: u64) -> Result<(), ContractError> {
+        let mut stream = load_stream(&env, stream_id)?;
+        Self::require_party_auth(&env, &stream.recipient)?;
+
+        if stream.status != StreamStatus::PendingAcceptance {
+            return Err(ContractError::InvalidState);
+        }
+
+        let now = env.ledger().timestamp();
+        let cliff_offset = stream.cliff_time - stream.start_time;
+        let duration = stream.end_time - stream.start_time;
+        stream.start_time = now;
+        stream.cliff_time = now
+            .checked_add(cliff_offset)
+            .expect("overflow rescheduling accepted stream");
+        stream.end_time = now
+            .checked_add(duration)
+            .expect("overflow rescheduling accepted stream");
+        stream.checkpoint_time = now;
+        stream.status = StreamStatus::Active;
+        save_stream(&env, &stream);
+
+        env.events().publish(
+            (symbol_short!("accepted"), stream_id),
+            StreamEvent::Accepted(stream_id),
+        );
+        Ok(())
+    }
+
+    /// Reject a stream that was created with `requires_acceptance`.
+    ///
+    /// Cancels the pending stream and refunds the full deposit to the sender. Nothing
+    /// is ever claimable by the recipient.
+    ///
+    /// # Parameters
+    /// - `stream_id`: Unique identifier of the stream to reject
+    ///
+    /// # Authorization
+    /// - Requires authorization from the stream's recipient
+    ///
+    /// # Errors
+    /// - `ContractError::StreamNotFound` if the stream does not exist
+    /// - `ContractError::InvalidState` if the stream is not `PendingAcceptance`
+    ///
+    /// # Events
+    /// - Publishes `Rejected(stream_id)` event on success
+    pub fn reject_stream(env: Env, stream_id: u64) -> Result<(), ContractError> {
+        let mut stream = load_stream(&env, stream_id)?;
+        Self::require_party_auth(&env, &stream.recipient)?;
+
+        if stream.status != StreamStatus::PendingAcceptance {
+            return Err(ContractError::InvalidState);
+        }
+
+        // CEI: update state before external token transfer to reduce reentrancy risk.
+        stream.cancelled_at = Some(Self::accrual_freeze_time(&env, &stream));
+        Self::forfeit_unstarted_accrual(&mut stream);
+        stream.status = StreamStatus::Cancelled;
+        let refund = stream.deposit_amount + take_security_deposit(&mut stream);
+        save_stream(&env, &stream);
+        decrement_active_stream_count(&env);
+
+        refund_sender(&env, &stream.sender, refund)?;
+
+        env.events().publish(
+            (symbol_short!("rejected"), stream_id),
+            StreamEvent::Rejected(stream_id),
+        );
+        Ok(())
+    }
+
+    /// Lengthen a stream at its current rate, funding the extra time up front.
+    ///
+    /// Moves `end_time` out by `additional_seconds` and pulls what the longer schedule
+    /// streams on top of the old one from the sender: `rate_per_second *
+    /// additional_seconds` for a linear stream, only the active seconds added for a
+    /// scheduled one. Accrual up to now is checkpointed first, so the extension only
+    /// affects time after `now`. Front-loaded and single-unlock streams cannot be
+    /// extended: their curves spread the whole deposit over the schedule, so part or
+    /// all of the extra deposit would unlock at once.
+    ///
+    /// # Parameters
+    /// - `stream_id`: Unique identifier of the stream to extend
+    /// - `additional_seconds`: Seconds to add to `end_time` (must be > 0)
+    ///
+    /// # Returns
+    /// - `i128`: The amount transferred from the sender
+    ///
+    /// # Authorization
+    /// - Requires authorization from the stream's sender
+    ///
+    /// # Errors
+    /// - `ContractError::StreamNotFound` if the stream does not exist
+    /// - `ContractError::InvalidState` if the stream is `Completed`, `Cancelled`, or
+    ///   `Unfunded`, or accrues `FrontLoaded` or `SingleUnlock`
+    /// - `ContractError::DepositsPaused` if the admin has paused deposits
+    /// - `ContractError::DepositExceedsCap` if the new deposit is above `max_deposit`
+    ///
+    /// # Panics
+    /// - If `additional_seconds` is zero
+    /// - If the new `end_time` or the extra deposit overflows
+    /// - If the token transfer fails (insufficient sender balance or allowance)
+    ///
+    /// # Events
+    /// - Publishes `Extended { stream_id, end_time, amount }` event on success
+    pub fn extend_stream(
+        env: Env,
+        stream_id: u64,
+        additional_seconds: u64,
+    ) -> Result<i128, ContractError> {
+        let mut stream = load_stream(&env, stream_id)?;
+        Self::require_party_auth(&env, &stream.sender)?;
+
+        if matches!(
+            stream.status,
+            StreamStatus::Completed
+                | StreamStatus::Cancelled
+                | StreamStatus::Settled
+                | StreamStatus::Unfunded
+        ) || matches!(
+            stream.accrual_kind,
+            AccrualKind::FrontLoaded(_) | AccrualKind::SingleUnlock
+        ) {
+            return Err(ContractError::InvalidState);
+        }
+        if get_config(&env).deposits_paused {
+            return Err(ContractError::DepositsPaused);
+        }
+        assert!(
+            additional_seconds > 0,
+            "additional_seconds must be positive"
+        );
+
+        let end_time = stream
+            .end_time
+            .checked_add(additional_seconds)
+            .expect("overflow calculating extended end_time");
+        let streamable_at = |end_time| {
+            curve_total_streamable(
+                &stream.accrual_kind,
+                stream.rate_per_second,
+                stream.start_time,
+                end_time,
+            )
+            .expect("overflow calculating extension deposit")
+        };
+        let amount = streamable_at(end_time) - streamable_at(stream.end_time);
+        let deposit_amount = stream
+            .deposit_amount
+            .checked_add(amount)
+            .expect("overflow calculating extension deposit");
+        if exceeds_deposit_cap(&env, deposit_amount) {
+            return Err(ContractError::DepositExceedsCap);
+        }
+
+        Self::checkpoint_accrual(&env, &mut stream);
+        // An uncapped stream stays uncapped; a capped one keeps its ceiling.
+        if stream.max_withdrawable == stream.deposit_amount {
+            stream.max_withdrawable = deposit_amount;
+        }
+        stream.deposit_amount = deposit_amount;
+        stream.end_time = end_time;
+        save_stream(&env, &stream);
+
+        if amount > 0 {
+            let token_client = token::Client::new(&env, &get_token(&env));
+            token_client.transfer(&stream.sender, &env.current_contract_address(), &amount);
+            add_to_total(&env, DataKey::TotalDeposited, amount);
+        }
+
+        env.events().publish(
+            (symbol_short!("extended"), stream_id),
+            StreamEvent::Extended(Extended {
+                stream_id,
+                end_time,
+                amount,
+            }),
+        );
+        Ok(amount)
+    }
+
+    /// Point a stream that has not started yet at a different recipient.
+    ///
+    /// Lets the sender correct a wrong recipient address while nothing can have
+    /// accrued. The stream moves from the old recipient's index to the new one's, and
+    /// any auto-withdraw authorization or withdraw hook set by the old recipient is
+    /// dropped.
+    ///
+    /// # Parameters
+    /// - `stream_id`: Unique identifier of the stream
+    /// - `new_recipient`: Address that will receive the stream
+    ///
+    /// # Authorization
+    /// - Requires authorization from the stream's sender
+    ///
+    /// # Errors
+    /// - `ContractError::StreamNotFound` if the stream does not exist
+    /// - `ContractError::StreamAlreadyStarted` if `now >= start_time`
+    /// - `ContractError::InvalidState` if the stream is `Completed` or `Cancelled`, or is
+    ///   a split stream
+    /// - `ContractError::InvalidRecipient` if `new_recipient` is this contract or the
+    ///   stream token
+    ///
+    /// # Panics
+    /// - If `new_recipient` is the sender
+    ///
+    /// # Events
+    /// - Publishes `RecipientAssigned { stream_id, old, new }` event on success
+    pub fn set_recipient_before_start(
+        env: Env,
+        stream_id: u64,
+        new_recipient: Address,
+    ) -> Result<(), ContractError> {
+        let mut stream = load_stream(&env, stream_id)?;
+        Self::require_party_auth(&env, &stream.sender)?;
+
+        if env.ledger().timestamp() >= stream.start_time {
+            return Err(ContractError::StreamAlreadyStarted);
+        }
+        if matches!(
+            stream.status,
+            StreamStatus::Completed | StreamStatus::Cancelled | StreamStatus::Settled
+        ) || load_split_weights(&env, stream_id).is_some()
+        {
+            return Err(ContractError::InvalidState);
+        }
+        assert!(
+            new_recipient != stream.sender,
+            "sender and recipient must be different"
+        );
+        if is_invalid_recipient(&env, &new_recipient) {
+            return Err(ContractError::InvalidRecipient);
+        }
+        if new_recipient == stream.recipient {
+            return Ok(());
+        }
+
+        let old_recipient = stream.recipient.clone();
+        unindex_stream_recipient(&env, &old_recipient, stream_id);
+        index_stream_recipient(&env, &new_recipient, stream_id);
+        env.storage()
+            .persistent()
+            .remove(&DataKey::AutoWithdrawUntil(stream_id));
+        stream.recipient = new_recipient.clone();
+        stream.on_withdraw_hook = None;
+        save_stream(&env, &stream);
+
+        env.events().publish(
+            (symbol_short!("recipient"), stream_id),
+            StreamEvent::RecipientAssigned(RecipientAssigned {
+                stream_id,
+                old: old_recipient,
+                new: new_recipient,
+            }),
+        );
+        Ok(())
+    }
+
+    /// Hand a stream over to a new sender.
+    ///
+    /// From then on every sender-only action (pause, resume, cancel, top-up, ...)
+    /// requires `new_sender`, and cancellation refunds go to `new_sender`. The stream
+    /// moves from the old sender's index to the new one's. Tokens already deposited
+    /// stay in the contract; nothing is transferred.
+    ///
+    /// # Parameters
+    /// - `stream_id`: Unique identifier of the stream
+    /// - `new_sender`: Address that takes over the stream
+    ///
+    /// # Authorization
+    /// - Requires authorization from the stream's current sender
+    ///
+    /// # Errors
+    /// - `ContractError::StreamNotFound` if the stream does not exist
+    /// - `ContractError::InvalidState` if the stream is `Completed` or `Cancelled`
+    /// - `ContractError::InvalidRecipient` if `new_sender` is this contract or the stream
+    ///   token
+    ///
+    /// # Panics
+    /// - If `new_sender` is the recipient
+    ///
+    /// # Events
+    /// - Publishes `SenderTransferred { stream_id, old, new }` event on success
+    pub fn transfer_sender(
+        env: Env,
+        stream_id: u64,
+        new_sender: Address,
+    ) -> Result<(), ContractError> {
+        let mut stream = load_stream(&env, stream_id)?;
+        Self::require_party_auth(&env, &stream.sender)?;
+
+        if matches!(
+            stream.status,
+            StreamStatus::Completed | StreamStatus::Cancelled | StreamStatus::Settled
+        ) {
+            return Err(ContractError::InvalidState);
+        }
+        assert!(
+            new_sender != stream.recipient,
+            "sender and recipient must be different"
+        );
+        if is_invalid_recipient(&env, &new_sender) {
+            return Err(ContractError::InvalidRecipient);
+        }
+        if new_sender == stream.sender {
+            return Ok(());
+        }
+
+        let old_sender = stream.sender.clone();
+        unindex_stream_sender(&env, &old_sender, stream_id);
+        index_stream_sender(&env, &new_sender, stream_id);
+        stream.sender = new_sender.clone();
+        save_stream(&env, &stream);
+
+        env.events().publish(
+            (Symbol::new(&env, "sender_transferred"), stream_id),
+            StreamEvent::SenderTransferred(SenderTransferred {
+                stream_id,
+                old: old_sender,
+                new: new_sender,
+            }),
+        );
+        Ok(())
+    }
+
+    /// Pause an active payment stream.
+    ///
+    /// Temporarily halts withdrawals from the stream while preserving accrual calculations.
+    /// The stream can be resumed later by the sender or admin. Accrual continues based on
+    /// time elapsed, but the recipient cannot withdraw while paused.
+    ///
+    /// # Parameters
+    /// - `stream_id`: Unique identifier of the stream to pause
+    ///
+    /// # Authorization
+    /// - Requires authorization from the stream's sender (original creator)
+    /// - Admin can use `pause_stream_as_admin` for administrative override
+    ///
+    /// # Panics
+    /// - If the stream is not in `Active` state (already paused, completed, or cancelled)
+    /// - If the stream does not exist (`stream_id` is invalid)
+    /// - If caller is not authorized (not the sender)
+    ///
+    /// # Events
+    /// - Publishes `Paused(stream_id)` event on success
+    ///
+    /// # Usage Notes
+    /// - Pausing does not affect accrual calculations (time-based)
+    /// - Recipient cannot withdraw while stream is paused
+    /// - Stream can be cancelled while paused
+    /// - Use `resume_stream` to reactivate withdrawals
+    pub fn pause_stream(env: Env, stream_id: u64) -> Result<(), ContractError> {
+        let stream = load_stream(&env, stream_id)?;
+
+        Self::require_sender_or_admin(&env, &stream.sender)?;
+
+        if stream.status == StreamStatus::Paused {
+            panic!("stream is already paused");
+        }
+
+        assert!(
+            stream.status == StreamStatus::Active,
+            "stream must be active to pause"
+        );
+
+        Self::pause_active_stream(&env, stream);
+        Ok(())
+    }
+
+    /// Ask the sender to pause a stream, as its recipient.
+    ///
+    /// Only the sender (or admin) can pause; this lets the recipient signal that they
+    /// want one, e.g. for a leave of absence. It sets `pause_requested`, which the next
+    /// `pause_stream` or `pause_stream_as_admin` clears. Accrual is not affected.
+    ///
+    /// # Parameters
+    /// - `stream_id`: Unique identifier of the stream
+    ///
+    /// # Authorization
+    /// - Requires authorization from the stream's recipient
+    ///
+    /// # Errors
+    /// - `ContractError::StreamNotFound` if the stream does not exist
+    /// - `ContractError::Unauthorized` if the recipient is this contract (split streams)
+    /// - `ContractError::InvalidState` if the stream is not `Active`
+    ///
+    /// # Events
+    /// - Publishes `PauseRequested(stream_id)` event on success
+    pub fn request_pause(env: Env, stream_id: u64) -> Result<(), ContractError> {
+        let mut stream = load_stream(&env, stream_id)?;
+        Self::require_party_auth(&env, &stream.recipient)?;
+
+        if stream.status != StreamStatus::Active {
+            return Err(ContractError::InvalidState);
+        }
+
+        stream.pause_requested = true;
+        save_stream(&env, &stream);
+
+        env.events().publish(
+            (Symbol::new(&env, "pause_requested"), stream_id),
+            StreamEvent::PauseRequested(stream_id),
+        );
+        Ok(())
+    }
+
+    /// Whether the recipient has an outstanding `request_pause` on the stream.
+    ///
+    /// # Errors
+    /// - `ContractError::StreamNotFound` if the stream does not exist
+    pub fn is_pause_requested(env: Env, stream_id: u64) -> Result<bool, ContractError> {
+        Ok(load_stream(&env, stream_id)?.pause_requested)
+    }
+
+    /// Resume a paused payment stream.
+    ///
+    /// Reactivates a paused stream, allowing the recipient to withdraw accrued funds again.
+    /// Only streams in `Paused` state can be resumed. Terminal states (Completed, Cancelled)
+    /// cannot be resumed.
+    ///
+    /// # Parameters
+    /// - `stream_id`: Unique identifier of the stream to resume
+    ///
+    /// # Authorization
+    /// - Requires authorization from the stream's sender (original creator)
+    /// - Admin can use `resume_stream_as_admin` for administrative override
+    ///
+    /// # Panics
+    /// - If the stream is `Active` (not paused, already running)
+    /// - If the stream is `Completed` (terminal state, cannot be resumed)
+    /// - If the stream is `Cancelled` (terminal state, cannot be resumed)
+    /// - If the stream does not exist (`stream_id` is invalid)
+    /// - If caller is not authorized (not the sender)
+    ///
+    /// # Events
+    /// - Publishes `Resumed(stream_id)` event on success
+    ///
+    /// # Usage Notes
+    /// - Only paused streams can be resumed
+    /// - Accrual calculations are time-based and unaffected by pause/resume
+    /// - After resume, recipient can immediately withdraw accrued funds
+    pub fn resume_stream(env: Env, stream_id: u64) -> Result<(), ContractError> {
+        let mut stream = load_stream(&env, stream_id)?;
+        Self::require_sender_or_admin(&env, &stream.sender)?;
+
+        match stream.status {
+            StreamStatus::Active => panic!("stream is active, not paused"),
+            StreamStatus::Completed => panic!("stream is completed"),
+            StreamStatus::Cancelled | StreamStatus::Settled => panic!("stream is cancelled"),
+            StreamStatus::PendingAcceptance => panic!("stream is pending acceptance"),
+            StreamStatus::Unfunded => panic!("stream is unfunded"),
+            StreamStatus::Paused => {}
+        }
+
+        Self::checkpoint_accrual(&env, &mut stream);
+        stream.status = StreamStatus::Active;
+        save_stream(&env, &stream);
+
+        env.events().publish(
+            (symbol_short!("resumed"), stream_id),
+            StreamEvent::Resumed(stream_id),
+        );
+        Ok(())
+    }
+
+    /// Resume a paused stream with its schedule pushed back by the time it was paused.
+    ///
+    /// Pausing does not stop accrual; resuming with this instead of `resume_stream`
+    /// takes the paused time out of the schedule. `start_time`, `cliff_time`,
+    /// `end_time`, and the accrual checkpoint all move out by
+    /// `paused_duration = now - paused_at`, so the stream picks up where it was when
+    /// paused, accrues at the same rate, and still streams its whole deposit, just
+    /// `paused_duration` later. A pending `initiate_cancel` keeps its
+    /// `cancel_effective_at`.
+    ///
+    /// # Parameters
+    /// - `stream_id`: Unique identifier of the stream to resume
+    ///
+    /// # Returns
+    /// - `u64`: The new `end_time`
+    ///
+    /// # Authorization
+    /// - Requires authorization from the stream's sender
+    ///
+    /// # Errors
+    /// - `ContractError::StreamNotFound` if the stream does not exist
+    /// - `ContractError::InvalidState` if the stream is not `Paused`
+    ///
+    /// # Panics
+    /// - If the shifted schedule overflows `u64`
+    ///
+    /// # Events
+    /// - Publishes `Resumed(stream_id)` event on success
+    pub fn resume_stream_extend(env: Env, stream_id: u64) -> Result<u64, ContractError> {
+        let mut stream = load_stream(&env, stream_id)?;
+        Self::require_sender_or_admin(&env, &stream.sender)?;
+
+        if stream.status != StreamStatus::Paused {
+            return Err(ContractError::InvalidState);
+        }
+
+        // No checkpoint here: accrual over the paused window is exactly what is dropped.
+        Self::shift_paused_schedule(&mut stream, env.ledger().timestamp())
+            .expect("overflow extending resumed stream");
+        stream.status = StreamStatus::Active;
+        save_stream(&env, &stream);
+
+        env.events().publish(
+            (symbol_short!("resumed"), stream_id),
+            StreamEvent::Resumed(stream_id),
+        );
+        Ok(stream.end_time)
+    }
+
+    /// Cancel a payment stream and refund unstreamed funds to the sender.
+    ///
+    /// Terminates an active or paused stream, immediately refunding any unstreamed tokens
+    /// to the sender. The accrued amount (based on time elapsed) remains in the contract
+    /// for the recipient to withdraw. This is a terminal operation - cancelled streams
+    /// cannot be resumed.
+    ///
+    /// # Parameters
+    /// - `stream_id`: Unique identifier of the stream to cancel
+    ///
+    /// # Authorization
+    /// - Requires authorization from the stream's sender (original creator)
+    /// - Admin can use `cancel_stream_as_admin` for administrative override
+    ///
+    /// # Behavior
+    /// 1. Validates stream is in `Active`, `Paused`, `PendingAcceptance`, or `Unfunded` state
+    /// 2. Calculates accrued amount: `min((now - start_time) × rate, deposit_amount)`
+    /// 3. Calculates refund: `deposit_amount - accrued`
+    /// 4. Sets stream status to `Cancelled` and records `cancelled_at` (single write)
+    /// 5. Transfers refund to sender (if > 0)
+    /// 6. Accrued but not withdrawn amount remains for recipient
+    ///
+    /// # Returns
+    /// - Implicitly returns via state change and token transfer
+    ///
+    /// # Panics
+    /// - If stream is not `Active`, `Paused`, `PendingAcceptance`, or `Unfunded` (already
+    ///   completed or cancelled)
+    /// - If the stream does not exist (`stream_id` is invalid)
+    /// - If caller is not authorized (not the sender)
+    ///
+    /// # Errors
+    /// - `ContractError::InsufficientContractBalance` if the contract cannot cover the refund
+    ///
+    /// # Events
+    /// - Publishes `Cancelled(stream_id)` event on success
+    ///
+    /// # Usage Notes
+    /// - Cancellation is irreversible (terminal state)
+    /// - Recipient can still withdraw accrued amount after cancellation
+    /// - If fully accrued (time >= end_time), sender receives no refund
+    /// - Accrual is time-based, not affected by pause state
+    /// - Can be called on paused streams
+    /// - A stream still pending acceptance is refunded in full
+    /// - A stream still `Unfunded` is cancelled with no refund, since nothing was deposited
+    ///
+    /// # Examples
+    /// - Cancel at 30% completion → sender gets 70% refund, recipient can withdraw 30%
+    /// - Cancel at 100% completion → sender gets 0% refund, recipient can withdraw 100%
+    /// - Cancel before cliff → sender gets 100% refund (no accrual before cliff)
+    pub fn cancel_stream(env: Env, stream_id: u64) -> Result<(), ContractError> {
+        let stream = load_stream(&env, stream_id)?;
+        Self::require_sender_or_admin(&env, &stream.sender)?;
+        Self::require_cancellable_status(&env, stream.status);
+
+        let cancelled_at = Self::accrual_freeze_time(&env, &stream);
+        record_sender_cancel(&env, &stream.sender);
+        Self::settle_cancellation(&env, stream, cancelled_at, None, None)
+    }
+
+    /// Cancel a stream and, with the recipient co-signing, pay out its accrual at once.
+    ///
+    /// With `settle == false` this is exactly `cancel_stream`. With `settle == true` the
+    /// recipient must also authorize the call, and after the cancellation the accrued
+    /// but unwithdrawn amount is transferred to them as `withdraw` would, so neither
+    /// party needs a second transaction. Soroban cannot detect an optional signature,
+    /// so the caller states whether the recipient co-signs through `settle`.
+    ///
+    /// # Parameters
+    /// - `stream_id`: Unique identifier of the stream to cancel
+    /// - `settle`: Also pay the recipient; requires the recipient's authorization
+    ///
+    /// # Returns
+    /// - `i128`: Amount paid to the recipient (`0` when not settling)
+    ///
+    /// # Authorization
+    /// - Requires authorization from the stream's sender
+    /// - With `settle`, also from the stream's recipient
+    ///
+    /// # Errors
+    /// - `ContractError::StreamNotFound` if the stream does not exist
+    /// - `ContractError::Unauthorized` if a required party is this contract
+    /// - `ContractError::InvalidState` if the stream is not `Active`, `Paused`,
+    ///   `PendingAcceptance`, or `Unfunded`
+    /// - `ContractError::WithdrawTooSoon` if settling inside the stream's
+    ///   `min_withdraw_interval`; the whole call reverts, cancellation included
+    /// - `ContractError::InsufficientContractBalance` if the contract cannot cover a transfer
+    ///
+    /// # Events
+    /// - Publishes `Cancelled(stream_id)`, then `withdrew(stream_id, amount)` if
+    ///   anything was paid to the recipient
+    pub fn cancel_and_settle(
+        env: Env,
+        stream_id: u64,
+        settle: bool,
+    ) -> Result<i128, ContractError> {
+        let stream = load_stream(&env, stream_id)?;
+        Self::require_sender_or_admin(&env, &stream.sender)?;
+        if settle {
+            Self::require_party_auth(&env, &stream.recipient)?;
+        }
+        Self::require_cancellable_status(&env, stream.status);
+
+        let cancelled_at = Self::accrual_freeze_time(&env, &stream);
+        record_sender_cancel(&env, &stream.sender);
+        Self::settle_cancellation(&env, stream, cancelled_at, None, None)?;
+        if !settle {
+            return Ok(0);
+        }
+        Self::settle_withdrawal(&env, load_stream(&env, stream_id)?)
+    }
+
+    /// Cancel a stream, sending the refund to any address and optionally paying out the
+    /// recipient in the same call.
+    ///
+    /// Cancels like `cancel_stream`, but the unstreamed refund (plus any security
+    /// deposit) goes to `refund_to` instead of the sender. With `pay_recipient`, the
+    /// accrued but unwithdrawn amount is then transferred to the recipient as
+    /// `withdraw` would, so the call settles everyone. Paying the recipient needs only
+    /// the sender's authorization, as with `revoke_stream`. While
+    /// `Config::cancel_payout_ack` is enabled that amount is instead held in escrow
+    /// until the recipient accepts it with `claim_cancelled`.
+    ///
+    /// # Parameters
+    /// - `stream_id`: Unique identifier of the stream to cancel
+    /// - `refund_to`: Address receiving the refund; may be the sender
+    /// - `pay_recipient`: Also pay the recipient its accrued balance now
+    ///
+    /// # Returns
+    /// - `i128`: Amount paid to the recipient (`0` when not paying or when escrowed)
+    ///
+    /// # Authorization
+    /// - Requires authorization from the stream's sender
+    ///
+    /// # Errors
+    /// - `ContractError::StreamNotFound` if the stream does not exist
+    /// - `ContractError::Unauthorized` if the sender is this contract
+    /// - `ContractError::InvalidRecipient` if `refund_to` is this contract or the token
+    /// - `ContractError::InvalidState` if the stream is not `Active`, `Paused`,
+    ///   `PendingAcceptance`, or `Unfunded`, or if `pay_recipient` is set on a split
+    ///   stream (its recipients claim with `withdraw_split` after the cancellation)
+    /// - `ContractError::WithdrawTooSoon` if paying inside the stream's
+    ///   `min_withdraw_interval`; the whole call reverts, cancellation included
+    /// - `ContractError::InsufficientContractBalance` if the contract cannot cover a transfer
+    ///
+    /// # Events
+    /// - Publishes `Cancelled(stream_id)`, then `withdrew(stream_id, amount)` if
+    ///   anything was paid to the recipient, or `escrowed(stream_id, amount)` if it was
+    ///   held for `claim_cancelled`
+    pub fn cancel_full(
+        env: Env,
+        stream_id: u64,
+        refund_to: Address,
+        pay_recipient: bool,
+    ) -> Result<i128, ContractError> {
+        let stream = load_stream(&env, stream_id)?;
+        Self::require_party_auth(&env, &stream.sender)?;
+        if is_invalid_recipient(&env, &refund_to) {
+            return Err(ContractError::InvalidRecipient);
+        }
+        // A split stream's recipient is this contract; its shares go out via `withdraw_split`.
+        if pay_recipient && load_split_weights(&env, stream_id).is_some() {
+            return Err(ContractError::InvalidState);
+        }
+        Self::require_cancellable_status(&env, stream.status);
+
+        let cancelled_at = Self::accrual_freeze_time(&env, &stream);
+        record_sender_cancel(&env, &stream.sender);
+        Self::settle_cancellation(&env, stream, cancelled_at, None, Some(refund_to))?;
+        if !pay_recipient {
+            return Ok(0);
+        }
+        let stream = load_stream(&env, stream_id)?;
+        if get_config(&env).cancel_payout_ack {
+            Self::escrow_cancel_payout(&env, stream)?;
+            return Ok(0);
+        }
+        Self::settle_withdrawal(&env, stream)
+    }
+
+    /// Claim a cancellation payout held in escrow by `cancel_full`.
+    ///
+    /// While `Config::cancel_payout_ack` is enabled, `cancel_full` with `pay_recipient`
+    /// does not push the recipient's accrued balance to them. It is set aside for the
+    /// stream and only paid out once the recipient acknowledges it by calling this.
+    ///
+    /// # Parameters
+    /// - `stream_id`: Unique identifier of the cancelled stream
+    ///
+    /// # Returns
+    /// - `i128`: Amount paid to the recipient
+    ///
+    /// # Authorization
+    /// - Requires authorization from the stream's recipient
+    ///
+    /// # Errors
+    /// - `ContractError::StreamNotFound` if the stream does not exist
+    /// - `ContractError::Unauthorized` if the recipient is this contract
+    /// - `ContractError::Reentrant` if called from within a withdraw hook
+    /// - `ContractError::InvalidState` if nothing is held in escrow for the stream
+    /// - `ContractError::InsufficientContractBalance` if the contract cannot cover the payout
+    ///
+    /// # Events
+    /// - Publishes `withdrew(stream_id, amount)` on success
+    /// - Also publishes `Settled(stream_id)` when the claim settles the stream
+    pub fn claim_cancelled(env: Env, stream_id: u64) -> Result<i128, ContractError> {
+        let mut stream = load_stream(&env, stream_id)?;
+        Self::require_party_auth(&env, &stream.recipient)?;
+        if env.storage().temporary().has(&DataKey::WithdrawLock) {
+            return Err(ContractError::Reentrant);
+        }
+
+        let amount = get_cancel_escrow(&env, stream_id);
+        if amount == 0 {
+            return Err(ContractError::InvalidState);
+        }
+
+        // CEI: release the escrow before the external token transfer.
+        let now = env.ledger().timestamp();
+        let accrued = Self::calculate_accrued(env.clone(), stream_id)?;
+        env.storage()
+            .persistent()
+            .remove(&DataKey::CancelEscrow(stream_id));
+        stream.last_withdraw_time = Some(now);
+        Self::settle_if_drained(&env, &mut stream, accrued);
+        save_stream(&env, &stream);
+        log_withdrawal(&env, stream_id, now, amount);
+
+        pay_recipient(&env, &stream.recipient, amount)?;
+
+        env.events()
+            .publish((symbol_short!("withdrew"), stream_id), amount);
+        Self::publish_settled(&env, &stream);
+        Ok(amount)
+    }
+
+    /// Amount held in escrow for `stream_id` until the recipient calls
+    /// `claim_cancelled`; `0` when nothing is held.
+    ///
+    /// # Usage Notes
+    /// - This is a view function (read-only, no state changes)
+    /// - No authorization required (public information)
+    pub fn get_cancel_escrow(env: Env, stream_id: u64) -> i128 {
+        get_cancel_escrow(&env, stream_id)
+    }
+
+    /// Start cancelling a stream with a notice period for the recipient.
+    ///
+    /// The stream stays `Active` (or `Paused`) and keeps accruing until
+    /// `cancel_effective_at = now + grace_seconds`. From that time accrual stops and
+    /// `finalize_cancel` refunds the sender exactly as `cancel_stream` would have at
+    /// `cancel_effective_at`. Both parties can see the pending cancellation in
+    /// `get_stream_state`. The sender can still cancel immediately with
+    /// `cancel_stream`.
+    ///
+    /// # Parameters
+    /// - `stream_id`: Unique identifier of the stream
+    /// - `grace_seconds`: Notice period before the cancellation takes effect
+    ///
+    /// # Returns
+    /// - `u64`: The `cancel_effective_at` timestamp
+    ///
+    /// # Authorization
+    /// - Requires authorization from the stream's sender
+    ///
+    /// # Errors
+    /// - `ContractError::StreamNotFound` if the stream does not exist
+    /// - `ContractError::InvalidState` if the stream is not `Active` or `Paused`, or a
+    ///   cancellation is already pending
+    ///
+    /// # Events
+    /// - Publishes `cancel_initiated(stream_id, cancel_effective_at)`
+    pub fn initiate_cancel(
+        env: Env,
+        stream_id: u64,
+        grace_seconds: u64,
+    ) -> Result<u64, ContractError> {
+        let mut stream = load_stream(&env, stream_id)?;
+        Self::require_sender_or_admin(&env, &stream.sender)?;
+
+        if !matches!(stream.status, StreamStatus::Active | StreamStatus::Paused)
+            || stream.cancel_effective_at.is_some()
+        {
+            return Err(ContractError::InvalidState);
+        }
+
+        let effective_at = env.ledger().timestamp().saturating_add(grace_seconds);
+        stream.cancel_effective_at = Some(effective_at);
+        save_stream(&env, &stream);
+
+        env.events().publish(
+            (Symbol::new(&env, "cancel_initiated"), stream_id),
+            effective_at,
+        );
+        Ok(effective_at)
+    }
+
+    /// Complete a cancellation started with `initiate_cancel`.
+    ///
+    /// Refunds `deposit_amount - accrued` to the sender, with accrual frozen at
+    /// `cancel_effective_at`, and marks the stream `Cancelled` with
+    /// `cancelled_at = cancel_effective_at`. What the recipient accrued stays
+    /// withdrawable.
+    ///
+    /// # Parameters
+    /// - `stream_id`: Unique identifier of the stream
+    ///
+    /// # Authorization
+    /// - None: the sender committed to the cancellation in `initiate_cancel`, so anyone
+    ///   (e.g. a keeper) may finalize it once it is due
+    ///
+    /// # Errors
+    /// - `ContractError::StreamNotFound` if the stream does not exist
+    /// - `ContractError::InvalidState` if no cancellation is pending or the stream is
+    ///   already terminal
+    /// - `ContractError::CancelNotYetEffective` if `now < cancel_effective_at`
+    /// - `ContractError::InsufficientContractBalance` if the contract cannot cover the refund
+    ///
+    /// # Events
+    /// - Publishes `Cancelled(stream_id)` event on success
+    pub fn finalize_cancel(env: Env, stream_id: u64) -> Result<(), ContractError> {
+        let stream = load_stream(&env, stream_id)?;
+        let effective_at = match stream.cancel_effective_at {
+            Some(effective_at)
+                if matches!(stream.status, StreamStatus::Active | StreamStatus::Paused) =>
+            {
+                effective_at
+            }
+            _ => return Err(ContractError::InvalidState),
+        };
+        if env.ledger().timestamp() < effective_at {
+            return Err(ContractError::CancelNotYetEffective);
+        }
+
+        record_sender_cancel(&env, &stream.sender);
+        Self::settle_cancellation(&env, stream, effective_at, None, None)
+    }
+
+    /// Give the unvested remainder of a stream back to the sender, as its recipient.
+    ///
+    /// Settles the stream in one call: the recipient receives everything accrued but not
+    /// yet withdrawn, the sender is refunded `deposit_amount - accrued`, and the stream
+    /// becomes `Cancelled`. Unlike `cancel_stream` it is initiated by the recipient, e.g.
+    /// to return a grant.
+    ///
+    /// # Parameters
+    /// - `stream_id`: Unique identifier of the stream to renounce
+    ///
+    /// # Authorization
+    /// - Requires authorization from the stream's recipient
+    ///
+    /// # Errors
+    /// - `ContractError::StreamNotFound` if the stream does not exist
+    /// - `ContractError::Unauthorized` if the recipient is this contract (split streams)
+    /// - `ContractError::InvalidState` if the stream is not `Active` or `Paused`
+    /// - `ContractError::InsufficientContractBalance` if the contract cannot cover a payout
+    ///
+    /// # Events
+    /// - Publishes `withdrew(stream_id, amount)` if accrued tokens were paid out
+    /// - Publishes `Renounced(stream_id)` event on success
+    ///
+    /// # Usage Notes
+    /// - A stream still pending acceptance is declined with `reject_stream` instead
+    pub fn renounce_stream(env: Env, stream_id: u64) -> Result<(), ContractError> {
+        let mut stream = load_stream(&env, stream_id)?;
+        Self::require_party_auth(&env, &stream.recipient)?;
+
+        if !matches!(stream.status, StreamStatus::Active | StreamStatus::Paused) {
+            return Err(ContractError::InvalidState);
+        }
+
+        let cancelled_at = Self::accrual_freeze_time(&env, &stream);
+        let accrued = Self::calculate_accrued(env.clone(), stream_id)?;
+        let payout = accrued - stream.withdrawn_amount;
+        let unstreamed = stream.deposit_amount - accrued;
+
+        // CEI: write the settled state before either external token transfer.
+        Self::checkpoint_accrual(&env, &mut stream);
+        let refund = unstreamed + take_security_deposit(&mut stream);
+        stream.withdrawn_amount = accrued;
+        stream.status = StreamStatus::Cancelled;
+        stream.cancelled_at = Some(cancelled_at);
+        save_stream(&env, &stream);
+        decrement_active_stream_count(&env);
+
+        if payout > 0 {
+            pay_recipient(&env, &stream.recipient, payout)?;
+        }
+        if refund > 0 {
+            refund_sender(&env, &stream.sender, refund)?;
+        }
+
+        if payout > 0 {
+            env.events()
+                .publish((symbol_short!("withdrew"), stream_id), payout);
+        }
+        env.events().publish(
+            (symbol_short!("renounced"), stream_id),
+            StreamEvent::Renounced(stream_id),
+        );
+        Ok(())
+    }
+
+    /// Revoke a vesting stream: pay out what has vested and refund the rest now.
+    ///
+    /// Unlike `cancel_stream`, which leaves the accrued amount in the contract for the
+    /// recipient to withdraw later, this transfers `accrued - withdrawn_amount` to the
+    /// recipient in the same call, refunds `deposit_amount - accrued` to the sender, and
+    /// marks the stream `Completed` with `max_withdrawable` lowered to the vested amount.
+    ///
+    /// # Parameters
+    /// - `stream_id`: Unique identifier of the stream to revoke
+    ///
+    /// # Returns
+    /// - `i128`: Amount paid to the recipient
+    ///
+    /// # Authorization
+    /// - Requires authorization from the stream's sender
+    /// - Admin can use `revoke_stream_as_admin`
+    ///
+    /// # Errors
+    /// - `ContractError::StreamNotFound` if the stream does not exist
+    /// - `ContractError::InvalidState` if the stream is not `Active` or `Paused`, or is a
+    ///   split stream
+    /// - `ContractError::InsufficientContractBalance` if the contract cannot cover a payout
+    ///
+    /// # Events
+    /// - Publishes `withdrew(stream_id, amount)` if vested tokens were paid out
+    /// - Publishes `Revoked(stream_id)`, then `Completed(stream_id)`
+    pub fn revoke_stream(env: Env, stream_id: u64) -> Result<i128, ContractError> {
+        let stream = load_stream(&env, stream_id)?;
+        Self::require_sender_or_admin(&env, &stream.sender)?;
+        Self::settle_revocation(&env, stream)
+    }
+
+    /// Withdraw accrued tokens from a payment stream to the recipient.
+    ///
+    /// Transfers all accrued-but-not-yet-withdrawn tokens to the stream's recipient.
+    /// The amount withdrawn is calculated as `accrued - withdrawn_amount`, where accrued
+    /// is based on time elapsed since stream start. If this withdrawal completes the
+    /// stream (all deposited tokens withdrawn), the stream status transitions to `Completed`.
+    ///
+    /// # Parameters
+    /// - `stream_id`: Unique identifier of the stream to withdraw from
+    ///
+    /// # Returns
+    /// - `i128`: The amount of tokens transferred to the recipient; `0` when nothing
+    ///   is withdrawable (`accrued == withdrawn_amount`), in which case no transfer
+    ///   happens and no event is published
+    ///
+    /// # Authorization
+    /// - Requires authorization from the stream's recipient (only recipient can withdraw)
+    /// - This prevents anyone from withdrawing on behalf of the recipient
+    /// - There are no operators: a call signed by anyone else fails inside
+    ///   `require_auth` with a host auth error, since `withdraw` has no caller argument
+    ///   from which to tell a third party apart
+    ///
+    /// # Panics
+    /// - If the stream is `PendingAcceptance`
+    /// - If caller is not authorized (not the recipient)
+    ///
+    /// # Errors
+    /// - `ContractError::StreamNotFound` if the stream does not exist
+    /// - `ContractError::AlreadyCompleted` if the stream is `Completed` (all tokens
+    ///   already withdrawn)
+    /// - `ContractError::StreamPaused` if the stream is `Paused`
+    /// - `ContractError::InvalidState` if the stream is `Unfunded`
+    /// - `ContractError::Unauthorized` if the recipient can never authorize the call
+    ///   (it is this contract, as for split streams)
+    /// - `ContractError::InsufficientContractBalance` if the contract cannot cover the payout
+    /// - `ContractError::WithdrawTooSoon` if the stream's `min_withdraw_interval` has not
+    ///   elapsed since `last_withdraw_time`
+    /// - `ContractError::BelowMinWithdraw` if the withdrawable amount is below the
+    ///   stream's `min_withdraw_amount` and the stream has not yet ended or been cancelled
+    ///
+    /// # State Changes
+    /// - Updates `withdrawn_amount` by the amount transferred
+    /// - Records `last_withdraw_time`
+    /// - Sets status to `Completed` if all deposited tokens are withdrawn
+    /// - Sets a `Cancelled` stream's status to `Settled` once its accrued amount is fully
+    ///   withdrawn, if `Config::settle_cancelled` is enabled
+    /// - Extends stream storage TTL to prevent expiration
+    ///
+    /// # Events
+    /// - Publishes `withdrew(stream_id, amount)` event on success
+    /// - Also publishes `Completed(stream_id)` when this withdrawal completes the stream
+    /// - Also publishes `Settled(stream_id)` when this withdrawal settles the stream
+    ///
+    /// # Usage Notes
+    /// - Can be called multiple times to withdraw incrementally
+    /// - Accrual is time-based: `min((now - start_time) × rate, deposit_amount)`
+    /// - Before cliff time, accrued amount is 0, so withdraw returns 0
+    /// - Idempotent: repeating a withdrawal at the same timestamp returns 0
+    /// - After end_time, accrued amount is capped at deposit_amount
+    /// - Works on `Active` and `Cancelled` streams, not on `Paused` or `Completed`
+    /// - For cancelled streams, only the accrued amount (not refunded) can be withdrawn
+    ///
+    /// # Examples
+    /// - Stream: 1000 tokens over 1000 seconds (1 token/sec)
+    /// - At t=300: withdraw() returns 300 tokens
+    /// - At t=800: withdraw() returns 500 tokens (800 - 300 already withdrawn)
+    /// - At t=1000: withdraw() returns 200 tokens, status → Completed
+    pub fn withdraw(env: Env, stream_id: u64) -> Result<i128, ContractError> {
+        let stream = load_stream(&env, stream_id)?;
+
+        // Enforce recipient-only authorization: only the stream's recipient can withdraw
+        // This is equivalent to checking env.invoker() == stream.recipient
+        // require_auth() ensures only the recipient can authorize this call,
+        // preventing anyone from withdrawing on behalf of the recipient
+        Self::require_party_auth(&env, &stream.recipient)?;
+
+        Self::settle_withdrawal(&env, stream)
+    }
+
+    /// Authorize permissionless withdrawals to the recipient until a deadline.
+    ///
+    /// Lets the recipient sign once instead of for every claim. Until `until`, anyone
+    /// (e.g. a keeper) may call `execute_auto_withdraw` to push accrued tokens to the
+    /// recipient. Tokens can only ever go to the stream's recipient.
+    ///
+    /// # Parameters
+    /// - `stream_id`: Unique identifier of the stream
+    /// - `until`: Last ledger timestamp (inclusive) at which auto-withdrawals are allowed
+    ///
+    /// # Authorization
+    /// - Requires authorization from the stream's recipient
+    ///
+    /// # Panics
+    /// - If the stream does not exist (`stream_id` is invalid)
+    /// - If caller is not authorized (not the recipient)
+    ///
+    /// # Events
+    /// - Publishes `auto_wd(stream_id, until)` event on success
+    ///
+    /// # Usage Notes
+    /// - Calling again replaces the previous window
+    /// - Passing an `until` in the past revokes the authorization
+    pub fn authorize_auto_withdraw(
+        env: Env,
+        stream_id: u64,
+        until: u64,
+    ) -> Result<(), ContractError> {
+        let stream = load_stream(&env, stream_id)?;
+        Self::require_party_auth(&env, &stream.recipient)?;
+
+        let key = DataKey::AutoWithdrawUntil(stream_id);
+        env.storage().persistent().set(&key, &until);
+        env.storage().persistent().extend_ttl(&key, 17280, 120960);
+
+        env.events()
+            .publish((symbol_short!("auto_wd"), stream_id), until);
+        Ok(())
+    }
+
+    /// Have a contract notified after every payout from a stream.
+    ///
+    /// Once set, each successful withdrawal (`withdraw`, `execute_auto_withdraw`, or a
+    /// settling `cancel_and_settle`) calls `stream_received(stream_id: u64, amount: i128)`
+    /// on `hook` after the tokens have been transferred, so a vault recipient can
+    /// deposit them right away. The call cannot re-enter a withdrawal, and a hook that
+    /// fails is ignored rather than reverting the payout.
+    ///
+    /// # Parameters
+    /// - `stream_id`: Unique identifier of the stream
+    /// - `hook`: Contract to notify; `None` removes the hook
+    ///
+    /// # Authorization
+    /// - Requires authorization from the stream's recipient
+    ///
+    /// # Errors
+    /// - `ContractError::StreamNotFound` if the stream does not exist
+    ///
+    /// # Events
+    /// - Publishes `hook_set(stream_id, hook)` event on success
+    pub fn set_withdraw_hook(
+        env: Env,
+        stream_id: u64,
+        hook: Option<Address>,
+    ) -> Result<(), ContractError> {
+        let mut stream = load_stream(&env, stream_id)?;
+        Self::require_party_auth(&env, &stream.recipient)?;
+
+        stream.on_withdraw_hook = hook.clone();
+        save_stream(&env, &stream);
+
+        env.events()
+            .publish((symbol_short!("hook_set"), stream_id), hook);
+        Ok(())
+    }
+
+    /// Withdraw accrued tokens to the recipient under a prior auto-withdraw authorization.
+    ///
+    /// Permissionless: no signature is required because the recipient pre-authorized
+    /// claims via `authorize_auto_withdraw`. Behaves exactly like `withdraw` otherwise.
+    ///
+    /// # Parameters
+    /// - `stream_id`: Unique identifier of the stream to withdraw from
+    ///
+    /// # Returns
+    /// - `i128`: The amount of tokens transferred to the recipient
+    ///
+    /// # Errors
+    /// - `ContractError::StreamNotFound` if the stream does not exist
+    /// - `ContractError::Unauthorized` if the recipient never authorized auto-withdrawals
+    /// - `ContractError::AuthorizationExpired` if `now > until`
+    /// - The same status and amount errors as `withdraw`
+    ///
+    /// # Panics
+    /// - If the stream is `PendingAcceptance`
+    ///
+    /// # Events
+    /// - Publishes `withdrew(stream_id, amount)` event on success
+    pub fn execute_auto_withdraw(env: Env, stream_id: u64) -> Result<i128, ContractError> {
+        let stream = load_stream(&env, stream_id)?;
+
+        let until: u64 = env
+            .storage()
+            .persistent()
+            .get(&DataKey::AutoWithdrawUntil(stream_id))
+            .ok_or(ContractError::Unauthorized)?;
+        if env.ledger().timestamp() > until {
+            return Err(ContractError::AuthorizationExpired);
+        }
+
+        Self::settle_withdrawal(&env, stream)
+    }
+
+    /// Return accrued-but-unwithdrawn tokens to the sender once the sweep window opens.
+    ///
+    /// Recipients who never claim leave tokens in the contract indefinitely. For streams
+    /// created with a nonzero `unclaimed_sweep_after`, the sender may reclaim whatever
+    /// is still unclaimed from `end_time + unclaimed_sweep_after` onwards. The swept
+    /// amount is counted as withdrawn, so nothing remains claimable by the recipient
+    /// and a fully funded stream becomes `Completed`.
+    ///
+    /// # Parameters
+    /// - `stream_id`: Unique identifier of the stream to sweep
+    ///
+    /// # Returns
+    /// - `i128`: The amount returned to the sender
+    ///
+    /// # Authorization
+    /// - Requires authorization from the stream's sender
+    ///
+    /// # Errors
+    /// - `ContractError::StreamNotFound` if the stream does not exist
+    /// - `ContractError::SweepNotAvailable` if sweeping is disabled for the stream or
+    ///   `now < end_time + unclaimed_sweep_after`
+    ///
+    /// # Panics
+    /// - If the stream is already `Completed`
+    /// - If nothing is left to sweep
+    ///
+    /// # Events
+    /// - Publishes `swept(stream_id, amount)` on success
+    /// - Also publishes `Completed(stream_id)` when the sweep completes the stream
+    pub fn sweep_unclaimed(env: Env, stream_id: u64) -> Result<i128, ContractError> {
+        let mut stream = load_stream(&env, stream_id)?;
+        Self::require_party_auth(&env, &stream.sender)?;
+
+        let now = env.ledger().timestamp();
+        if stream.unclaimed_sweep_after == 0
+            || now < stream.end_time.saturating_add(stream.unclaimed_sweep_after)
+        {
+            return Err(ContractError::SweepNotAvailable);
+        }
+
+        assert!(
+            stream.status != StreamStatus::Completed,
+            "stream already completed"
+        );
+
+        let accrued = Self::calculate_accrued(env.clone(), stream_id)?;
+        let unclaimed = accrued - stream.withdrawn_amount;
+        assert!(unclaimed > 0, "nothing to sweep");
+
+        // CEI: update state before external token transfer to reduce reentrancy risk.
+        Self::checkpoint_accrual(&env, &mut stream);
+        stream.withdrawn_amount += unclaimed;
+        stream.swept_amount += unclaimed;
+        let buffer = Self::complete_if_fully_withdrawn(&env, &mut stream, now);
+        Self::settle_if_drained(&env, &mut stream, accrued);
+        save_stream(&env, &stream);
+
+        refund_sender(&env, &stream.sender, unclaimed + buffer)?;
+
+        env.events()
+            .publish((symbol_short!("swept"), stream_id), unclaimed);
+        if stream.status == StreamStatus::Completed {
+            env.events().publish(
+                (symbol_short!("completed"), stream_id),
+                StreamEvent::Completed(stream_id),
+            );
+        }
+        Self::publish_settled(&env, &stream);
+        Ok(unclaimed)
+    }
+
+    /// Create a stream whose payout is split between several recipients by weight.
+    ///
+    /// Behaves like `create_stream` for funding, schedule, pause, and cancellation, but
+    /// the accrued amount is shared: a recipient with weight `w` is entitled to
+    /// `accrued * w / sum(weights)` and claims it with `withdraw_split`. The stream's
+    /// own `recipient` is this contract, so the plain `withdraw` path is closed.
+    ///
+    /// # Parameters
+    /// - `sender`: Address funding the stream (must authorize the transaction)
+    /// - `recipients`: `(recipient, weight)` pairs; weights must be nonzero, recipients
+    ///   distinct and different from the sender
+    /// - `deposit_amount`, `rate_per_second`, `start_time`, `cliff_time`, `end_time`:
+    ///   Same meaning and validation as `create_stream`
+    ///
+    /// # Returns
+    /// - `u64`: Unique stream identifier for the newly created stream
+    ///
+    /// # Panics
+    /// - If `recipients` is empty, has a zero weight, repeats an address, or contains
+    ///   the sender
+    /// - Under the same conditions as `create_stream`
+    ///
+    /// # Events
+    /// - Publishes `created(sender, recipient, (stream_id, deposit_amount))` event on success
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_split_stream(
+        env: Env,
+        sender: Address,
+        recipients: Vec<(Address, u32)>,
+        deposit_amount: i128,
+        rate_per_second: i128,
+        start_time: u64,
+        cliff_time: u64,
+        end_time: u64,
+    ) -> u64 {
+        assert!(!recipients.is_empty(), "split recipients must not be empty");
+        for (i, (recipient, weight)) in recipients.iter().enumerate() {
+            assert!(weight > 0, "split weights must be nonzero");
+            for (other, _) in recipients.iter().skip(i + 1) {
+                assert!(recipient != other, "split recipients must be distinct");
+            }
+        }
+
+        let stream_id = Self::open_stream(
+            &env,
+            sender.clone(),
+            sender,
+            env.current_contract_address(),
+            deposit_amount,
+            rate_per_second,
+            start_time,
+            cliff_time,
+            end_time,
+            CreateStreamOptions::default(),
+            Some(&recipients),
+        );
+
+        let key = DataKey::SplitWeights(stream_id);
+        env.storage().persistent().set(&key, &recipients);
+        env.storage().persistent().extend_ttl(&key, 17280, 120960);
+
+        stream_id
+    }
+
+    /// Withdraw one recipient's share of a split stream.
+    ///
+    /// Shares are assigned by cumulative weight, so the shares of all recipients always
+    /// add up to exactly the accrued amount and the stream can complete without
+    /// rounding dust.
+    ///
+    /// # Parameters
+    /// - `stream_id`: Unique identifier of a stream created by `create_split_stream`
+    /// - `recipient`: The recipient claiming their share
+    ///
+    /// # Returns
+    /// - `i128`: The amount transferred to `recipient`; `0` when the recipient has
+    ///   nothing to withdraw, in which case no transfer happens and no event is published
+    ///
+    /// # Authorization
+    /// - Requires authorization from `recipient`
+    ///
+    /// # Errors
+    /// - `ContractError::StreamNotFound` if the stream does not exist
+    /// - `ContractError::InvalidState` if the stream is not a split stream
+    /// - `ContractError::Unauthorized` if `recipient` is not in the split table
+    /// - `ContractError::AlreadyCompleted` if the stream is `Completed`
+    /// - `ContractError::StreamPaused` if the stream is `Paused`
+    ///
+    /// # Events
+    /// - Publishes `split_wd(stream_id, (recipient, amount))` on success
+    /// - Also publishes `Completed(stream_id)` when this withdrawal completes the stream
+    pub fn withdraw_split(
+        env: Env,
+        stream_id: u64,
+        recipient: Address,
+    ) -> Result<i128, ContractError> {
+        let mut stream = load_stream(&env, stream_id)?;
+        let weights = load_split_weights(&env, stream_id).ok_or(ContractError::InvalidState)?;
+        Self::require_party_auth(&env, &recipient)?;
+
+        match stream.status {
+            StreamStatus::Completed => return Err(ContractError::AlreadyCompleted),
+            StreamStatus::Paused => return Err(ContractError::StreamPaused),
+            _ => {}
+        }
+
+        let total_weight = weights.iter().fold(0u64, |sum, (_, w)| sum + w as u64);
+        let mut weight_before = 0u64;
+        let mut weight = None;
+        for (address, w) in weights.iter() {
+            if address == recipient {
+                weight = Some(w as u64);
+                break;
+            }
+            weight_before += w as u64;
+        }
+        let weight = weight.ok_or(ContractError::Unauthorized)?;
+
+        let accrued_amount = Self::calculate_accrued(env.clone(), stream_id)?;
+        let accrued = I256::from_i128(&env, accrued_amount);
+        let total = I256::from_i128(&env, total_weight as i128);
+        let portion = |cumulative: u64| {
+            accrued
+                .mul(&I256::from_i128(&env, cumulative as i128))
+                .div(&total)
+                .to_i128()
+                .expect("split share fits i128")
+        };
+        let entitled = portion(weight_before + weight) - portion(weight_before);
+
+        let already = get_split_withdrawn(&env, stream_id, &recipient);
+        let withdrawable = entitled - already;
+        if withdrawable == 0 {
+            return Ok(0);
+        }
+
+        // CEI: update state before external token transfer to reduce reentrancy risk.
+        Self::checkpoint_accrual(&env, &mut stream);
+        set_split_withdrawn(&env, stream_id, &recipient, entitled);
+        stream.withdrawn_amount += withdrawable;
+        let buffer = Self::complete_if_fully_withdrawn(&env, &mut stream, env.ledger().timestamp());
+        Self::settle_if_drained(&env, &mut stream, accrued_amount);
+        save_stream(&env, &stream);
+
+        pay_recipient(&env, &recipient, withdrawable)?;
+        if buffer > 0 {
+            refund_sender(&env, &stream.sender, buffer)?;
+        }
+
+        env.events().publish(
+            (symbol_short!("split_wd"), stream_id),
+            (recipient, withdrawable),
+        );
+        if stream.status == StreamStatus::Completed {
+            env.events().publish(
+                (symbol_short!("completed"), stream_id),
+                StreamEvent::Completed(stream_id),
+            );
+        }
+        Self::publish_settled(&env, &stream);
+        Ok(withdrawable)
+    }
+
+    /// Internal helper paying out the withdrawable amount once authorization has passed.
+    fn settle_withdrawal(env: &Env, mut stream: Stream) -> Result<i128, ContractError> {
+        let stream_id = stream.stream_id;
+        if env.storage().temporary().has(&DataKey::WithdrawLock) {
+            return Err(ContractError::Reentrant);
+        }
+
+        match stream.status {
+            StreamStatus::Completed => return Err(ContractError::AlreadyCompleted),
+            StreamStatus::Paused => return Err(ContractError::StreamPaused),
+            StreamStatus::Unfunded => return Err(ContractError::InvalidState),
+            _ => {}
+        }
+
+        assert!(
+            stream.status != StreamStatus::PendingAcceptance,
+            "stream is pending acceptance"
+        );
+
+        // Idempotent: with nothing accrued since the last withdrawal this is a no-op
+        // (no transfer, no event, no cooldown), so retries and keepers never fail here.
+        let accrued = Self::calculate_accrued(env.clone(), stream_id)?;
+        let withdrawable = accrued - stream.withdrawn_amount;
+        if withdrawable == 0 {
+            return Ok(0);
+        }
+
+        let now = env.ledger().timestamp();
+        if let Some(last) = stream.last_withdraw_time {
+            if now < last.saturating_add(stream.min_withdraw_interval) {
+                return Err(ContractError::WithdrawTooSoon);
+            }
+        }
+
+        // The final remainder is exempt so dust can never be stranded in the contract.
+        let is_final = stream.status == StreamStatus::Cancelled
+            || now >= stream.end_time
+            || stream.cancel_effective_at.is_some_and(|at| now >= at);
+        if !is_final && withdrawable < stream.min_withdraw_amount {
+            return Err(ContractError::BelowMinWithdraw);
+        }
+
+        // CEI: update state before external token transfer to reduce reentrancy risk.
+        Self::checkpoint_accrual(env, &mut stream);
+        stream.withdrawn_amount += withdrawable;
+        stream.last_withdraw_time = Some(now);
+        let buffer = Self::complete_if_fully_withdrawn(env, &mut stream, now);
+        Self::settle_if_drained(env, &mut stream, accrued);
+        save_stream(env, &stream);
+        log_withdrawal(env, stream_id, now, withdrawable);
+
+        pay_recipient(env, &stream.recipient, withdrawable)?;
+        if buffer > 0 {
+            refund_sender(env, &stream.sender, buffer)?;
+        }
+
+        env.events()
+            .publish((symbol_short!("withdrew"), stream_id), withdrawable);
+        if stream.status == StreamStatus::Completed {
+            env.events().publish(
+                (symbol_short!("completed"), stream_id),
+                StreamEvent::Completed(stream_id),
+            );
+        }
+        Self::publish_settled(env, &stream);
+        if let Some(hook) = &stream.on_withdraw_hook {
+            notify_withdraw_hook(env, hook, stream_id, withdrawable);
+        }
+        Ok(withdrawable)
+    }
+
+    /// Calculate the total amount accrued to the recipient at the current time.
+    ///
+    /// # Behaviour by status
+    ///
+    /// | Status      | Return value                                         |
+    /// |-------------|------------------------------------------------------|
+    /// | `Active`    | `min((now - start) × rate, deposit_amount)`          |
+    /// | `Paused`    | Same time-based formula (accrual is not paused)      |
+    /// | `Completed` | `deposit_amount` — all tokens were accrued/withdrawn |
+    /// | `Cancelled` | Final accrued at cancellation timestamp (frozen value) |
+    /// | `Settled`   | Same as `Cancelled`                                  |
+    ///
+    /// ## Rationale for `Cancelled`
+    /// On cancellation, unstreamed tokens are refunded immediately to the sender.
+    /// The recipient can claim only what was already accrued at cancellation time.
+    /// Returning a frozen final accrued value keeps `calculate_accrued` consistent
+    /// with contract balances and prevents post-cancel time growth.
+    ///
+    /// # Calculation
+    /// - Before `cliff_time`: returns 0 (no accrual before cliff)
+    /// - After `cliff_time`: `min((now - start_time) × rate_per_second, deposit_amount)`
+    /// - After `end_time`: capped at `deposit_amount` (no accrual beyond end)
+    ///
+    /// Evaluated in O(1) as `accrued_checkpoint + (now - checkpoint_time) × rate`, clamped
+    /// to `deposit_amount`. Withdraw, pause, resume, and cancel move the checkpoint
+    /// forward; for a stream whose rate never changes this equals the formula above.
+    ///
+    /// # Panics
+    /// - If the stream does not exist (`stream_id` is invalid)
+    ///
+    /// # Usage Notes
+    /// - This is a view function (read-only, no state changes)
+    /// - No authorization required (public information)
+    /// - Returns total accrued, not withdrawable amount
+    /// - To get withdrawable amount: `calculate_accrued() - stream.withdrawn_amount`
+    /// - Active/Paused streams accrue by current time; Completed/Cancelled are deterministic
+    /// - Useful for UIs to show real-time accrual without transactions
+    ///
+    /// # Examples
+    /// - Stream: 1000 tokens, 0-1000s, rate 1 token/sec, cliff at 500s
+    /// - At t=300: returns 0 (before cliff)
+    /// - At t=500: returns 500 (at cliff, accrual from start_time)
+    /// - At t=800: returns 800
+    /// - At t=1500: returns 1000 (capped at deposit_amount)
+    /// ## Rationale for `Completed`
+    /// When a stream reaches `Completed`, `withdrawn_amount == deposit_amount`.
+    /// There is no further accrual possible. Returning `deposit_amount` is the
+    /// deterministic, timestamp-independent answer for any UI or downstream caller.
+    pub fn calculate_accrued(env: Env, stream_id: u64) -> Result<i128, ContractError> {
+        let stream = load_stream(&env, stream_id)?;
+
+        if stream.status == StreamStatus::Completed {
+            return Ok(stream.max_withdrawable);
+        }
+
+        if matches!(
+            stream.status,
+            StreamStatus::PendingAcceptance | StreamStatus::Unfunded
+        ) {
+            return Ok(0);
+        }
+
+        let now = if matches!(
+            stream.status,
+            StreamStatus::Cancelled | StreamStatus::Settled
+        ) {
+            stream
+                .cancelled_at
+                .expect("cancelled stream missing cancelled_at timestamp")
+        } else {
+            env.ledger().timestamp()
+        };
+
+        Ok(Self::accrued_at(&stream, now))
+    }
+
+    /// Rate, in tokens per second, at which the stream is accruing right now.
+    ///
+    /// Measured from the stream's current checkpoint and curve as the accrual over the
+    /// next second, so it follows the schedule actually in effect rather than the
+    /// `rate_per_second` the stream was created with: it is `0` before the cliff, after
+    /// `end_time`, past a pending cancellation's `cancel_effective_at`, and once
+    /// `max_withdrawable` is reached, and shrinks over time for front-loaded streams.
+    ///
+    /// # Parameters
+    /// - `stream_id`: Unique identifier of the stream to query
+    ///
+    /// # Returns
+    /// - `i128`: Tokens accruing per second now; `0` for any stream that is not
+    ///   `Active` (paused, pending, unfunded, or terminal)
+    ///
+    /// # Errors
+    /// - `ContractError::StreamNotFound` if the stream does not exist
+    ///
+    /// # Usage Notes
+    /// - This is a view function (read-only, no state changes)
+    /// - No authorization required (public information)
+    pub fn get_effective_rate(env: Env, stream_id: u64) -> Result<i128, ContractError> {
+        let stream = load_stream(&env, stream_id)?;
+        if stream.status != StreamStatus::Active {
+            return Ok(0);
+        }
+
+        // Skip the catch-up jump at the cliff: nothing is accruing before it.
+        let now = env.ledger().timestamp();
+        if now < stream.cliff_time {
+            return Ok(0);
+        }
+        Ok(Self::accrued_at(&stream, now.saturating_add(1)) - Self::accrued_at(&stream, now))
+    }
+
+    /// Accrued amount if the stream were paused now and resumed after `pause_seconds`.
+    ///
+    /// Simulates `pause_stream` at the current time followed, `pause_seconds` later, by
+    /// `resume_stream_extend`, and returns what `calculate_accrued` would report right
+    /// after that resume. A stream that is already `Paused` stays paused from its
+    /// original `paused_at`. A pending `initiate_cancel` still caps accrual at its
+    /// unshifted `cancel_effective_at`, as it would for a real pause.
+    ///
+    /// # Parameters
+    /// - `stream_id`: Unique identifier of the stream
+    /// - `pause_seconds`: Length of the hypothetical pause, starting now
+    ///
+    /// # Errors
+    /// - `ContractError::StreamNotFound` if the stream does not exist
+    /// - `ContractError::InvalidState` if the stream is not `Active` or `Paused`
+    /// - `ContractError::Overflow` if the resume time or shifted schedule overflows `u64`
+    ///
+    /// # Usage Notes
+    /// - This is a view function (read-only, no state changes)
+    pub fn preview_after_pause(
+        env: Env,
+        stream_id: u64,
+        pause_seconds: u64,
+    ) -> Result<i128, ContractError> {
+        let mut stream = load_stream(&env, stream_id)?;
+        let now = env.ledger().timestamp();
+
+        match stream.status {
+            StreamStatus::Active => {
+                Self::checkpoint_accrual(&env, &mut stream);
+                stream.paused_at = Some(now);
+            }
+            StreamStatus::Paused => {}
+            _ => return Err(ContractError::InvalidState),
+        }
+
+        let resume_at = now
+            .checked_add(pause_seconds)
+            .ok_or(ContractError::Overflow)?;
+        Self::shift_paused_schedule(&mut stream, resume_at).ok_or(ContractError::Overflow)?;
+        Ok(Self::accrued_at(&stream, resume_at))
+    }
+
+    /// The deposit a linear stream with these parameters must cover.
+    ///
+    /// Performs the same `rate_per_second * (end_time - start_time)` check as stream
+    /// creation, so UIs can show the required deposit and catch overflow before
+    /// submitting. Other creation rules (positive rate, `start_time < end_time`, cliff
+    /// range) are not checked; `end_time <= start_time` yields `0`.
+    ///
+    /// # Errors
+    /// - `ContractError::Overflow` if the product does not fit in an `i128`
+    ///
+    /// # Usage Notes
+    /// - This is a view function (read-only, no state changes)
+    /// - With a `creation_fee_bps` set, the deposit must still cover this amount after
+    ///   the fee is taken out of it
+    pub fn compute_total_streamable(
+        _env: Env,
+        rate_per_second: i128,
+        start_time: u64,
+        end_time: u64,
+    ) -> Result<i128, ContractError> {
+        total_streamable(rate_per_second, start_time, end_time).ok_or(ContractError::Overflow)
+    }
+
+    /// Timestamp `calculate_accrued` evaluates the stream at.
+    ///
+    /// | Status              | Return value                                             |
+    /// |---------------------|----------------------------------------------------------|
+    /// | `Active` / `Paused` | Ledger time, capped at a pending `cancel_effective_at`   |
+    /// | `PendingAcceptance` | `start_time` (nothing accrues before acceptance)         |
+    /// | `Unfunded`          | `start_time` (nothing accrues before funding)            |
+    /// | `Cancelled`         | `cancelled_at` (accrual is frozen)                       |
+    /// | `Completed`         | `end_time`                                               |
+    ///
+    /// Accrual itself never runs past `end_time`, so a live value beyond it still
+    /// yields the full schedule.
+    ///
+    /// # Errors
+    /// - `ContractError::StreamNotFound` if the stream does not exist
+    pub fn get_accrual_timestamp(env: Env, stream_id: u64) -> Result<u64, ContractError> {
+        let stream = load_stream(&env, stream_id)?;
+        Ok(match stream.status {
+            StreamStatus::Cancelled | StreamStatus::Settled => stream
+                .cancelled_at
+                .expect("cancelled stream missing cancelled_at timestamp"),
+            StreamStatus::Completed => stream.end_time,
+            _ => Self::accrual_freeze_time(&env, &stream),
+        })
+    }
+
+    /// Retrieve the global contract configuration.
+    ///
+    /// Returns the contract's configuration containing the token address used for all
+    /// streams and the admin address authorized for administrative operations.
+    ///
+    /// # Returns
+    /// - `Config`: Structure containing:
+    ///   - `token`: Address of the token contract used for all payment streams
+    ///   - `admin`: Address authorized to perform admin operations (pause, cancel, resume)
+    ///
+    /// # Panics
+    /// - If the contract has not been initialized (missing config)
+    ///
+    /// # Usage Notes
+    /// - This is a view function (read-only, no state changes)
+    /// - No authorization required (public information)
+    /// - Config is set once during `init()` and can be updated via `set_admin()`
+    /// - Useful for integrators to verify token and admin addresses
+    pub fn get_config(env: Env) -> Config {
+        get_config(&env)
+    }
+
+    /// Retrieve the global contract configuration without panicking.
+    ///
+    /// Same as `get_config`, but an uninitialised contract yields a typed error that
+    /// clients can match on instead of a panic message.
+    ///
+    /// # Errors
+    /// - `ContractError::NotInitialized` if `init` has not been called
+    pub fn get_config_checked(env: Env) -> Result<Config, ContractError> {
+        try_get_config(&env).ok_or(ContractError::NotInitialized)
+    }
+
+    /// Update the admin address for the contract.
+    ///
+    /// Allows the current admin to rotate the admin key by setting a new admin address.
+    /// This enables key rotation without redeploying the contract. Only the current admin
+    /// may call this function.
+    ///
+    /// # Parameters
+    /// - `new_admin`: The new admin address that will replace the current admin
+    ///
+    /// # Authorization
+    /// - Requires authorization from the current admin address
+    ///
+    /// # Panics
+    /// - If the contract has not been initialized (missing config)
+    /// - If caller is not the current admin
+    ///
+    /// # Errors
+    /// - `ContractError::SameAdmin` if `new_admin` is already the admin
+    ///
+    /// # State Changes
+    /// - Updates the admin address in the Config stored in instance storage
+    /// - Token address remains unchanged
+    ///
+    /// # Events
+    /// - Publishes `StreamEvent::AdminRotated { old, new }` under the
+    ///   `(admin, updated)` topic on success
+    ///
+    /// # Usage Notes
+    /// - This is a security-critical function for admin key rotation
+    /// - The new admin immediately gains all administrative privileges
+    /// - The old admin immediately loses all administrative privileges
+    /// - The new admin must differ from the current one; a no-op rotation is rejected
+    ///   so monitoring never sees a rotation event that changed nothing
+    /// - Can be called multiple times to rotate keys as needed
+    ///
+    /// # Examples
+    /// - Rotate to a new admin key: `set_admin(env, new_admin_address)`
+    /// - Transfer admin to a multisig: `set_admin(env, multisig_address)`
+    pub fn set_admin(env: Env, new_admin: Address) {
+        let mut config = get_config(&env);
+        let old_admin = config.admin.clone();
+
+        // Only current admin can update admin
+        old_admin.require_auth();
+
+        if new_admin == old_admin {
+            panic_with_error!(&env, ContractError::SameAdmin);
+        }
+
+        // Update admin in config
+        config.admin = new_admin.clone();
+        set_config(&env, &config);
+
+        env.events().publish(
+            (symbol_short!("admin"), symbol_short!("updated")),
+            StreamEvent::AdminRotated(AdminRotated {
+                old: old_admin,
+                new: new_admin,
+            }),
+        );
+    }
+
+    /// Upgrade the contract code in place to a previously uploaded wasm.
+    ///
+    /// Replaces the executable of this contract instance while keeping its address,
+    /// balances, and all instance/persistent storage. This lets the protocol ship
+    /// fixes without redeploying and migrating every stream.
+    ///
+    /// # Parameters
+    /// - `new_wasm_hash`: Hash of the wasm already uploaded to the network
+    ///
+    /// # Authorization
+    /// - Requires authorization from the current admin address
+    ///
+    /// # Panics
+    /// - If the contract has not been initialized (missing config)
+    /// - If caller is not the current admin
+    /// - If no wasm with `new_wasm_hash` has been uploaded
+    ///
+    /// # Events
+    /// - Publishes `upgraded(new_wasm_hash)` event on success
+    ///
+    /// # Storage Compatibility
+    /// Storage is not migrated. The new code must keep the existing layout readable:
+    /// - `DataKey` variants must keep their names and payload types
+    /// - `Config` and `Stream` must keep their field names and types; adding a field
+    ///   requires a migration path for entries written by the old code
+    /// - `StreamStatus` and `ContractError` discriminants must not be renumbered
+    ///
+    /// Entries of the original release are read lazily: a `LegacyConfig` under
+    /// `DataKey::Config` and `LegacyStream`s under `DataKey::Stream` are decoded with
+    /// defaults for the newer fields and rewritten under `DataKey::ConfigV2` and
+    /// `DataKey::StreamV2` on their next write. The stream counters and per-party
+    /// indexes do not cover such streams; run `rebuild_counters` after upgrading.
+    pub fn upgrade(env: Env, new_wasm_hash: BytesN<32>) {
+        get_admin(&env).require_auth();
+
+        env.deployer()
+            .update_current_contract_wasm(new_wasm_hash.clone());
+
+        env.events()
+            .publish((symbol_short!("upgraded"),), new_wasm_hash);
+    }
+
+    /// Retrieve the complete state of a payment stream.
+    ///
+    /// Returns all stored information about a stream including participants, amounts,
+    /// timing parameters, and current status. This is a read-only view function.
+    ///
+    /// # Parameters
+    /// - `stream_id`: Unique identifier of the stream to query
+    ///
+    /// # Returns
+    /// - `Stream`: Complete stream state containing:
+    ///   - `stream_id`: Unique identifier
+    ///   - `sender`: Address that created and funded the stream
+    ///   - `recipient`: Address that receives the streamed tokens
+    ///   - `deposit_amount`: Total tokens deposited (initial funding)
+    ///   - `rate_per_second`: Streaming rate (tokens per second)
+    ///   - `start_time`: When streaming begins (ledger timestamp)
+    ///   - `cliff_time`: When tokens first become available (vesting cliff)
+    ///   - `end_time`: When streaming completes (ledger timestamp)
+    ///   - `withdrawn_amount`: Total tokens already withdrawn by recipient
+    ///   - `status`: Current stream status (Active, Paused, Completed, Cancelled)
+    ///   - `cancelled_at`: Timestamp at which accrual was frozen by cancellation
+    ///   - `completed_at`: Timestamp of the withdrawal that completed the stream
+    ///   - `min_withdraw_interval`: Minimum seconds between withdrawals (`0` = no cooldown)
+    ///   - `last_withdraw_time`: Timestamp of the most recent withdrawal
+    ///   - `min_withdraw_amount`: Smallest amount a non-final withdrawal may move
+    ///   - `accrued_checkpoint`: Amount accrued as of `checkpoint_time`
+    ///   - `checkpoint_time`: Time of the last accrual checkpoint (`start_time` initially)
+    ///   - `unclaimed_sweep_after`: Sweep window offset after `end_time` (`0` = disabled)
+    ///   - `swept_amount`: Unclaimed accrual returned to the sender by `sweep_unclaimed`
+    ///   - `cliff_unlock_amount`: Upfront amount released at the cliff
+    ///   - `accrual_kind`: Release curve (`Linear`, `FrontLoaded`, `SingleUnlock` or
+    ///     `Scheduled`)
+    ///   - `last_updated`: Ledger timestamp of the last state change, for staleness checks
+    ///   - `cancel_effective_at`: When a cancellation started by `initiate_cancel` takes effect
+    ///
+    /// # Panics
+    /// - If the stream does not exist (`stream_id` is invalid)
+    ///
+    /// # Usage Notes
+    /// - This is a view function (read-only, no state changes)
+    /// - No authorization required (public information)
+    /// - Useful for UIs to display stream details
+    /// - Combine with `calculate_accrued()` to show real-time withdrawable amount, or
+    ///   call `get_stream_full()` to get both at once
+    /// - Status indicates current operational state:
+    ///   - `Active`: Normal operation, recipient can withdraw
+    ///   - `Paused`: Temporarily halted, no withdrawals allowed
+    ///   - `Completed`: All tokens withdrawn, terminal state
+    ///   - `Cancelled`: Terminated early, unstreamed tokens refunded, terminal state
+    pub fn get_stream_state(env: Env, stream_id: u64) -> Result<Stream, ContractError> {
+        load_stream(&env, stream_id)
+    }
+
+    /// Return a stream's stored state together with its live accrual.
+    ///
+    /// Saves clients a second `calculate_accrued` call and re-deriving what is
+    /// withdrawable. `get_stream_state` keeps returning the stored `Stream` alone.
+    ///
+    /// # Parameters
+    /// - `stream_id`: Unique identifier of the stream to query
+    ///
+    /// # Returns
+    /// - `StreamView`: The `Stream`, `accrued = calculate_accrued(stream_id)`, and
+    ///   `withdrawable = accrued - withdrawn_amount`
+    ///
+    /// # Errors
+    /// - `ContractError::StreamNotFound` if the stream does not exist
+    ///
+    /// # Usage Notes
+    /// - This is a view function (read-only, no state changes)
+    /// - No authorization required (public information)
+    /// - `withdrawable` ignores pause and withdrawal limits; `withdraw` may still reject
+    ///   the call (e.g. `StreamPaused`, `WithdrawTooSoon`)
+    pub fn get_stream_full(env: Env, stream_id: u64) -> Result<StreamView, ContractError> {
+        let stream = load_stream(&env, stream_id)?;
+        let accrued = Self::calculate_accrued(env, stream_id)?;
+        let withdrawable = accrued - stream.withdrawn_amount;
+        Ok(StreamView {
+            stream,
+            accrued,
+            withdrawable,
+        })
+    }
+
+    /// Return the full state of every stored stream in `[start_id, start_id + limit)`.
+    ///
+    /// Lets explorers and dashboards load a page of streams in one call instead of one
+    /// `get_stream_state` call per id.
+    ///
+    /// # Parameters
+    /// - `start_id`: First stream id of the page
+    /// - `limit`: Number of ids to scan; clamped to `MAX_STREAMS_PAGE`
+    ///
+    /// # Returns
+    /// - `Vec<Stream>`: The streams found, in ascending id order. Ids whose entry is
+    ///   gone (archived or expired) are skipped, so the result may hold fewer than
+    ///   `limit` streams; continue from `start_id + limit`.
+    ///
+    /// # Usage Notes
+    /// - This is a view function (read-only, no state changes)
+    /// - Ids at or beyond the stream count are never scanned
+    pub fn get_streams_page(env: Env, start_id: u64, limit: u32) -> Vec<Stream> {
+        let limit = limit.min(MAX_STREAMS_PAGE) as u64;
+        let end_id = start_id.saturating_add(limit).min(get_stream_count(&env));
+
+        let mut streams = Vec::new(&env);
+        for stream_id in start_id..end_id {
+            if let Ok(stream) = load_stream(&env, stream_id) {
+                streams.push_back(stream);
+            }
+        }
+        streams
+    }
+
+    /// Ids of the streams in `[start_id, start_id + limit)` whose status is `status`.
+    ///
+    /// Serves "show me every paused stream" style dashboards. There is no per-status
+    /// index, so this scans stream ids one by one; the scan window, not the number of
+    /// matches, is what `limit` bounds.
+    ///
+    /// # Parameters
+    /// - `status`: Status to match
+    /// - `start_id`: First stream id to scan
+    /// - `limit`: Number of ids to scan; clamped to `MAX_STREAMS_PAGE`
+    ///
+    /// # Returns
+    /// - `Vec<u64>`: Matching ids in ascending order. The result may be shorter than
+    ///   `limit`, or empty, even when later streams match; continue from
+    ///   `start_id + limit`.
+    ///
+    /// # Usage Notes
+    /// - This is a view function (read-only, no state changes)
+    /// - Missing ids (archived or expired) are skipped; ids at or beyond the stream
+    ///   count are never scanned
+    pub fn get_streams_by_status(
+        env: Env,
+        status: StreamStatus,
+        start_id: u64,
+        limit: u32,
+    ) -> Vec<u64> {
+        let limit = limit.min(MAX_STREAMS_PAGE) as u64;
+        let end_id = start_id.saturating_add(limit).min(get_stream_count(&env));
+
+        let mut ids = Vec::new(&env);
+        for stream_id in start_id..end_id {
+            if let Ok(stream) = load_stream(&env, stream_id) {
+                if stream.status == status {
+                    ids.push_back(stream_id);
+                }
+            }
+        }
+        ids
+    }
+
+    /// Ids of the non-terminal streams in `[start_id, start_id + limit)` whose
+    /// `end_time` is at or before `timestamp`.
+    ///
+    /// Lets keepers that bump TTLs or finish off streams find the ones ending soon.
+    /// There is no index by end time, so this is an O(n) scan of stream ids; the scan
+    /// window, not the number of matches, is what `limit` bounds.
+    ///
+    /// # Parameters
+    /// - `timestamp`: Latest `end_time` to match (inclusive)
+    /// - `start_id`: First stream id to scan
+    /// - `limit`: Number of ids to scan; clamped to `MAX_STREAMS_PAGE`
+    ///
+    /// # Returns
+    /// - `Vec<u64>`: Matching ids in ascending order. `Completed`, `Cancelled` and
+    ///   `Settled` streams never match. The result may be shorter than `limit`, or
+    ///   empty, even when later streams match; continue from `start_id + limit`.
+    ///
+    /// # Usage Notes
+    /// - This is a view function (read-only, no state changes)
+    /// - Missing ids (archived or expired) are skipped; ids at or beyond the stream
+    ///   count are never scanned
+    pub fn get_streams_ending_before(
+        env: Env,
+        timestamp: u64,
+        start_id: u64,
+        limit: u32,
+    ) -> Vec<u64> {
+        let limit = limit.min(MAX_STREAMS_PAGE) as u64;
+        let end_id = start_id.saturating_add(limit).min(get_stream_count(&env));
+
+        let mut ids = Vec::new(&env);
+        for stream_id in start_id..end_id {
+            if let Ok(stream) = load_stream(&env, stream_id) {
+                let terminal = matches!(
+                    stream.status,
+                    StreamStatus::Completed | StreamStatus::Cancelled | StreamStatus::Settled
+                );
+                if !terminal && stream.end_time <= timestamp {
+                    ids.push_back(stream_id);
+                }
+            }
+        }
+        ids
+    }
+
+    /// Return the state of each requested stream, positionally.
+    ///
+    /// Lets a UI resolve a user's whole portfolio in one call after fetching its ids,
+    /// without one missing id reverting the whole read.
+    ///
+    /// # Parameters
+    /// - `stream_ids`: Ids to look up; at most `MAX_STREAMS_PAGE`
+    ///
+    /// # Returns
+    /// - `Vec<Option<Stream>>`: Same length and order as `stream_ids`; `None` where the
+    ///   stream does not exist (never created, archived, or expired)
+    ///
+    /// # Panics
+    /// - If more than `MAX_STREAMS_PAGE` ids are requested
+    ///
+    /// # Usage Notes
+    /// - This is a view function (read-only, no state changes)
+    pub fn get_stream_state_many(env: Env, stream_ids: Vec<u64>) -> Vec<Option<Stream>> {
+        assert!(
+            stream_ids.len() <= MAX_STREAMS_PAGE,
+            "too many stream ids requested"
+        );
+
+        let mut streams = Vec::new(&env);
+        for stream_id in stream_ids.iter() {
+            streams.push_back(load_stream(&env, stream_id).ok());
+        }
+        streams
+    }
+
+    /// Ids of every stream created with `category`, in creation order.
+    ///
+    /// Streams stay listed after they complete or are cancelled; combine with
+    /// `get_stream_state_many` to filter by status. Unknown categories yield an empty
+    /// vector.
+    pub fn get_streams_by_category(env: Env, category: Symbol) -> Vec<u64> {
+        get_category_streams(&env, &category)
+    }
+
+    /// Ids of every stream paying `recipient`, in creation order.
+    ///
+    /// Streams stay listed after they complete or are cancelled. Unknown recipients
+    /// yield an empty vector.
+    pub fn get_streams_by_recipient(env: Env, recipient: Address) -> Vec<u64> {
+        get_recipient_streams(&env, &recipient)
+    }
+
+    /// Recent withdrawals from a stream as `(timestamp, amount)` pairs, oldest first.
+    ///
+    /// Every payout through `withdraw`, `execute_auto_withdraw`, or a settling
+    /// `cancel_and_settle` is recorded. Only the latest `MAX_WITHDRAW_LOG` entries are
+    /// kept; `withdrawn_amount` remains the complete total. Streams with no
+    /// withdrawals, and unknown ids, yield an empty vector.
+    pub fn get_withdraw_history(env: Env, stream_id: u64) -> Vec<(u64, i128)> {
+        get_withdraw_log(&env, stream_id)
+    }
+
+    /// Ids of every stream owned by `sender`, in creation order.
+    ///
+    /// Streams stay listed after they complete or are cancelled. Unknown senders yield
+    /// an empty vector.
+    pub fn get_streams_by_sender(env: Env, sender: Address) -> Vec<u64> {
+        get_sender_streams(&env, &sender)
+    }
+
+    /// Sum of what `recipient` could withdraw right now across their streams.
+    ///
+    /// Adds `accrued - withdrawn_amount` for each `Active` or `Cancelled` stream in the
+    /// recipient index. `Paused` streams are skipped since they cannot be withdrawn
+    /// from, as are `Completed`, `PendingAcceptance`, and `Unfunded` streams, which
+    /// have nothing withdrawable. Per-stream withdrawal limits (`min_withdraw_amount`,
+    /// `min_withdraw_interval`) are not applied.
+    ///
+    /// Only the recipient's first `MAX_STREAMS_PAGE` streams (in creation order) are
+    /// read, keeping the call within ledger read limits; amounts on later streams are
+    /// not included.
+    ///
+    /// # Usage Notes
+    /// - This is a view function (read-only, no state changes)
+    /// - No authorization required (public information)
+    pub fn get_total_withdrawable(env: Env, recipient: Address) -> i128 {
+        let now = env.ledger().timestamp();
+        let mut total: i128 = 0;
+        for stream_id in get_recipient_streams(&env, &recipient)
+            .iter()
+            .take(MAX_STREAMS_PAGE as usize)
+        {
+            let Ok(stream) = load_stream(&env, stream_id) else {
+                continue;
+            };
+            let at = match stream.status {
+                StreamStatus::Active => now,
+                StreamStatus::Cancelled | StreamStatus::Settled => stream
+                    .cancelled_at
+                    .expect("cancelled stream missing cancelled_at timestamp"),
+                _ => continue,
+            };
+            let withdrawable = (Self::accrued_at(&stream, at) - stream.withdrawn_amount).max(0);
+            total = total
+                .checked_add(withdrawable)
+                .expect("overflow summing withdrawable amounts");
+        }
+        total
+    }
+
+    /// Compute a digest of a stream's schedule for quick equivalence checks.
+    ///
+    /// Hashes only the fields that shape the release schedule, so two streams with the
+    /// same schedule produce the same digest regardless of participants, deposit,
+    /// withdrawals, or status. Useful when cloning or restarting a stream to confirm
+    /// the new one vests identically.
+    ///
+    /// # Parameters
+    /// - `stream_id`: Unique identifier of the stream to query
+    ///
+    /// # Returns
+    /// - `BytesN<32>`: SHA-256 of `rate_per_second (16 bytes BE) || start_time (8 bytes BE)
+    ///   || cliff_time (8 bytes BE) || end_time (8 bytes BE)`, followed by
+    ///   `halflife_seconds (8 bytes BE)` for front-loaded streams or
+    ///   `'S' || days (4 bytes BE) || hours (4 bytes BE)` for scheduled ones, then
+    ///   `cliff_unlock_amount (16 bytes BE)` when it is nonzero
+    ///
+    /// # Panics
+    /// - If the stream does not exist (`stream_id` is invalid)
+    ///
+    /// # Usage Notes
+    /// - This is a view function (read-only, no state changes)
+    /// - No authorization required (public information)
+    /// - Linear streams without a cliff unlock hash exactly the four fields above, so
+    ///   their digests are unchanged by the addition of other release curves
+    pub fn get_schedule_digest(env: Env, stream_id: u64) -> Result<BytesN<32>, ContractError> {
+        let stream = load_stream(&env, stream_id)?;
+
+        let mut preimage = Bytes::new(&env);
+        preimage.extend_from_array(&stream.rate_per_second.to_be_bytes());
+        preimage.extend_from_array(&stream.start_time.to_be_bytes());
+        preimage.extend_from_array(&stream.cliff_time.to_be_bytes());
+        preimage.extend_from_array(&stream.end_time.to_be_bytes());
+        match &stream.accrual_kind {
+            AccrualKind::FrontLoaded(curve) => {
+                preimage.extend_from_array(&curve.halflife_seconds.to_be_bytes());
+            }
+            AccrualKind::Scheduled(mask) => {
+                // The tag keeps a mask from hashing like a front-loaded half-life.
+                preimage.push_back(b'S');
+                preimage.extend_from_array(&mask.days.to_be_bytes());
+                preimage.extend_from_array(&mask.hours.to_be_bytes());
+            }
+            AccrualKind::Linear | AccrualKind::SingleUnlock => {}
+        }
+        if stream.cliff_unlock_amount != 0 {
+            preimage.extend_from_array(&stream.cliff_unlock_amount.to_be_bytes());
+        }
+
+        Ok(env.crypto().sha256(&preimage).to_bytes())
+    }
+
+    /// Check whether a stream can be archived by a keeper to reclaim rent.
+    ///
+    /// A stream is archivable when all of the following hold:
+    /// - it is terminal (`Completed` or `Cancelled`)
+    /// - it is fully settled: nothing accrued remains for the recipient to withdraw,
+    ///   and no escrowed cancel payout awaits `claim_cancelled`
+    /// - it terminated more than `grace` seconds ago (`now - terminated_at > grace`)
+    ///
+    /// The termination time is `completed_at` for completed streams and `cancelled_at`
+    /// for cancelled ones.
+    ///
+    /// # Parameters
+    /// - `stream_id`: Unique identifier of the stream to query
+    /// - `grace`: Seconds a terminal stream is kept around before it may be archived
+    ///
+    /// # Returns
+    /// - `bool`: `true` if the stream is eligible for archival
+    ///
+    /// # Panics
+    /// - If the stream does not exist (`stream_id` is invalid)
+    ///
+    /// # Usage Notes
+    /// - This is a view function (read-only, no state changes)
+    /// - No authorization required (public information)
+    pub fn is_archivable(env: Env, stream_id: u64, grace: u64) -> Result<bool, ContractError> {
+        let stream = load_stream(&env, stream_id)?;
+
+        let terminated_at = match stream.status {
+            StreamStatus::Completed => stream.completed_at,
+            StreamStatus::Cancelled | StreamStatus::Settled => stream.cancelled_at,
+            _ => return Ok(false),
+        };
+        let Some(terminated_at) = terminated_at else {
+            return Ok(false);
+        };
+
+        let accrued = Self::calculate_accrued(env.clone(), stream_id)?;
+        // An escrowed payout already counts as withdrawn but is still owed.
+        if accrued > stream.withdrawn_amount || get_cancel_escrow(&env, stream_id) > 0 {
+            return Ok(false);
+        }
+
+        Ok(env.ledger().timestamp().saturating_sub(terminated_at) > grace)
+    }
+
+    /// Report how far the ledger clock is through a stream's schedule, in basis points.
+    ///
+    /// Computed as `(now - start_time) * 10000 / (end_time - start_time)`, clamped to
+    /// `[0, 10000]`. This is pure wall-clock progress and ignores the cliff, pauses,
+    /// cancellation and withdrawals, so it can differ from accrual progress. Intended
+    /// for a "time" progress bar shown next to the amount-based one.
+    ///
+    /// # Parameters
+    /// - `stream_id`: Unique identifier of the stream to query
+    ///
+    /// # Returns
+    /// - `u32`: Elapsed fraction of the schedule in basis points (`0..=10000`)
+    ///
+    /// # Panics
+    /// - If the stream does not exist (`stream_id` is invalid)
+    ///
+    /// # Usage Notes
+    /// - This is a view function (read-only, no state changes)
+    /// - No authorization required (public information)
+    pub fn get_time_elapsed_bps(env: Env, stream_id: u64) -> Result<u32, ContractError> {
+        let stream = load_stream(&env, stream_id)?;
+
+        let now = env.ledger().timestamp();
+        if now <= stream.start_time {
+            return Ok(0);
+        }
+        if now >= stream.end_time {
+            return Ok(10_000);
+        }
+
+        // start_time < now < end_time, so both differences are positive and the
+        // product fits in u128.
+        let elapsed = (now - stream.start_time) as u128;
+        let duration = (stream.end_time - stream.start_time) as u128;
+        Ok((elapsed * 10_000 / duration) as u32)
+    }
+
+    /// Whether the stream's recipient currently has tokens to withdraw.
+    ///
+    /// A cheap, non-reverting check for enabling a withdraw button.
+    ///
+    /// # Parameters
+    /// - `stream_id`: Unique identifier of the stream to query
+    ///
+    /// # Returns
+    /// - `true` only if the stream is `Active` or `Cancelled` and
+    ///   `calculate_accrued - withdrawn_amount > 0`
+    /// - `false` for paused, completed, pending, or missing streams
+    ///
+    /// # Usage Notes
+    /// - This is a view function (read-only, no state changes)
+    /// - Per-stream `min_withdraw_interval` / `min_withdraw_amount` limits are not
+    ///   considered; `withdraw` may still reject the call with `WithdrawTooSoon` or
+    ///   `BelowMinWithdraw`
+    pub fn is_withdrawable(env: Env, stream_id: u64) -> bool {
+        let Ok(stream) = load_stream(&env, stream_id) else {
+            return false;
+        };
+        if !matches!(
+            stream.status,
+            StreamStatus::Active | StreamStatus::Cancelled
+        ) {
+            return false;
+        }
+        Self::calculate_accrued(env, stream_id)
+            .is_ok_and(|accrued| accrued > stream.withdrawn_amount)
+    }
+
+    /// Check a stream's stored state against the core accounting invariants.
+    ///
+    /// A read-only diagnostic for monitoring. Checks, in order, that
+    /// `withdrawn_amount <= deposit_amount`, `start_time < end_time`,
+    /// `cliff_time` is within `[start_time, end_time]`, and
+    /// `withdrawn_amount <= calculate_accrued`.
+    ///
+    /// # Parameters
+    /// - `stream_id`: Unique identifier of the stream to check
+    ///
+    /// # Errors
+    /// - `ContractError::StreamNotFound` if the stream does not exist
+    /// - The first violated invariant: `WithdrawnExceedsDeposit`, `InvalidTimeRange`,
+    ///   `CliffOutOfRange`, or `WithdrawnExceedsAccrued`
+    ///
+    /// # Usage Notes
+    /// - This is a view function (read-only, no state changes)
+    /// - No authorization required (public information)
+    pub fn check_stream_invariants(env: Env, stream_id: u64) -> Result<(), ContractError> {
+        let stream = load_stream(&env, stream_id)?;
+
+        if stream.withdrawn_amount > stream.deposit_amount {
+            return Err(ContractError::WithdrawnExceedsDeposit);
+        }
+        if stream.start_time >= stream.end_time {
+            return Err(ContractError::InvalidTimeRange);
+        }
+        if stream.cliff_time < stream.start_time || stream.cliff_time > stream.end_time {
+            return Err(ContractError::CliffOutOfRange);
+        }
+        if stream.withdrawn_amount > Self::calculate_accrued(env, stream_id)? {
+            return Err(ContractError::WithdrawnExceedsAccrued);
+        }
+        Ok(())
+    }
+
+    /// Report a stream's amount-based progress and the time left until `end_time`.
+    ///
+    /// Saves UIs from deriving "X% complete, Y seconds left" off-chain.
+    ///
+    /// # Parameters
+    /// - `stream_id`: Unique identifier of the stream to query
+    ///
+    /// # Returns
+    /// - `(u32, u64)`: `(percent_bps, seconds_remaining)` where `percent_bps` is
+    ///   `accrued * 10000 / max_withdrawable` and `seconds_remaining` is
+    ///   `end_time - now` (saturating at 0)
+    /// - `Completed` streams report `(10000, 0)` and `Cancelled` streams `(0, 0)`:
+    ///   neither progresses any further
+    ///
+    /// # Errors
+    /// - `ContractError::StreamNotFound` if the stream does not exist
+    ///
+    /// # Usage Notes
+    /// - This is a view function (read-only, no state changes)
+    /// - No authorization required (public information)
+    pub fn get_stream_progress(env: Env, stream_id: u64) -> Result<(u32, u64), ContractError> {
+        let stream = load_stream(&env, stream_id)?;
+
+        match stream.status {
+            StreamStatus::Completed => return Ok((10_000, 0)),
+            StreamStatus::Cancelled | StreamStatus::Settled => return Ok((0, 0)),
+            _ => {}
+        }
+
+        let accrued = Self::calculate_accrued(env.clone(), stream_id)?;
+        // accrued <= max_withdrawable, so the quotient is at most 10000; I256 keeps the
+        // intermediate product from overflowing for very large deposits.
+        let percent_bps = I256::from_i128(&env, accrued)
+            .mul(&I256::from_i128(&env, 10_000))
+            .div(&I256::from_i128(&env, stream.max_withdrawable))
+            .to_i128()
+            .unwrap_or(0) as u32;
+        let seconds_remaining = stream.end_time.saturating_sub(env.ledger().timestamp());
+
+        Ok((percent_bps, seconds_remaining))
+    }
+
+    /// Check whether `who` could cancel a stream right now.
+    ///
+    /// Mirrors the rules enforced by the cancellation entrypoints so UIs can decide
+    /// whether to show a "Cancel" action:
+    /// - the sender may cancel via `cancel_stream` while the stream is `Active`,
+    ///   `Paused`, `PendingAcceptance`, or `Unfunded`
+    /// - the admin may cancel via `cancel_stream_as_admin` while it is `Active` or `Paused`
+    /// - nobody else (including the recipient) may cancel
+    ///
+    /// # Parameters
+    /// - `stream_id`: Unique identifier of the stream to query
+    /// - `who`: Address whose cancellation rights are evaluated
+    ///
+    /// # Returns
+    /// - `bool`: `true` if a cancellation by `who` would pass the status and role checks
+    ///
+    /// # Panics
+    /// - If the stream does not exist (`stream_id` is invalid)
+    ///
+    /// # Usage Notes
+    /// - This is a view function (read-only, no state changes)
+    /// - No authorization required; `who` still has to sign the actual cancellation
+    pub fn can_cancel(env: Env, stream_id: u64, who: Address) -> Result<bool, ContractError> {
+        let stream = load_stream(&env, stream_id)?;
+
+        // The contract can never sign a direct invocation of itself.
+        if who == env.current_contract_address() {
+            return Ok(false);
+        }
+
+        let as_sender = who == stream.sender
+            && matches!(
+                stream.status,
+                StreamStatus::Active
+                    | StreamStatus::Paused
+                    | StreamStatus::PendingAcceptance
+                    | StreamStatus::Unfunded
+            );
+        let as_admin = who == get_admin(&env)
+            && matches!(stream.status, StreamStatus::Active | StreamStatus::Paused);
+
+        Ok(as_sender || as_admin)
+    }
+
+    /// Preview the token split a cancellation would produce at the current time.
+    ///
+    /// Uses the same accrual as `cancel_stream`, without changing state.
+    ///
+    /// # Parameters
+    /// - `stream_id`: Unique identifier of the stream to query
+    ///
+    /// # Returns
+    /// - `(i128, i128)`: `(refund_to_sender, retained_for_recipient)` where
+    ///   `refund_to_sender = deposit_amount - accrued` and
+    ///   `retained_for_recipient = accrued - withdrawn_amount`; `(0, 0)` for an
+    ///   `Unfunded` stream, which holds no tokens
+    ///
+    /// # Errors
+    /// - `ContractError::StreamNotFound` if the stream does not exist
+    /// - `ContractError::InvalidState` if the stream is `Completed` or `Cancelled`
+    ///
+    /// # Usage Notes
+    /// - This is a view function (read-only, no state changes)
+    /// - No authorization required (public information)
+    pub fn preview_cancel(env: Env, stream_id: u64) -> Result<(i128, i128), ContractError> {
+        let stream = load_stream(&env, stream_id)?;
+
+        if matches!(
+            stream.status,
+            StreamStatus::Completed | StreamStatus::Cancelled | StreamStatus::Settled
+        ) {
+            return Err(ContractError::InvalidState);
+        }
+
+        if stream.status == StreamStatus::Unfunded {
+            return Ok((0, 0));
+        }
+
+        let accrued = Self::calculate_accrued(env, stream_id)?;
+        Ok((
+            stream.deposit_amount - accrued,
+            accrued - stream.withdrawn_amount,
+        ))
+    }
+
+    /// Preview the combined token split of cancelling several streams now.
+    ///
+    /// Sums `preview_cancel` over `stream_ids` so an admin can size the refund
+    /// obligation of a mass cancellation (e.g. before deprecating a token) in one call.
+    /// Terminal and missing streams are skipped.
+    ///
+    /// # Parameters
+    /// - `stream_ids`: Ids to include; at most `MAX_STREAMS_PAGE`
+    ///
+    /// # Returns
+    /// - `(i128, i128)`: `(refund_to_senders, retained_for_recipients)` totalled over
+    ///   the non-terminal streams
+    ///
+    /// # Panics
+    /// - If more than `MAX_STREAMS_PAGE` ids are requested
+    ///
+    /// # Usage Notes
+    /// - This is a view function (read-only, no state changes)
+    /// - No authorization required (public information)
+    pub fn preview_batch_cancel(env: Env, stream_ids: Vec<u64>) -> (i128, i128) {
+        assert!(
+            stream_ids.len() <= MAX_STREAMS_PAGE,
+            "too many stream ids requested"
+        );
+
+        let mut refund_total: i128 = 0;
+        let mut retained_total: i128 = 0;
+        for stream_id in stream_ids.iter() {
+            let Ok((refund, retained)) = Self::preview_cancel(env.clone(), stream_id) else {
+                continue;
+            };
+            refund_total = refund_total
+                .checked_add(refund)
+                .expect("overflow summing refund amounts");
+            retained_total = retained_total
+                .checked_add(retained)
+                .expect("overflow summing retained amounts");
+        }
+        (refund_total, retained_total)
+    }
+
+    /// Principal not yet streamed: what the sender would reclaim by cancelling now.
+    ///
+    /// `deposit_amount - calculate_accrued` for `Active`, `Paused`, and
+    /// `PendingAcceptance` streams. `0` for `Completed` and `Cancelled` streams, which
+    /// have nothing left to reclaim, and for `Unfunded` streams, which hold no tokens.
+    /// Any `security_deposit` is not included.
+    ///
+    /// # Errors
+    /// - `ContractError::StreamNotFound` if the stream does not exist
+    ///
+    /// # Usage Notes
+    /// - This is a view function (read-only, no state changes)
+    /// - No authorization required (public information)
+    pub fn get_unstreamed(env: Env, stream_id: u64) -> Result<i128, ContractError> {
+        let stream = load_stream(&env, stream_id)?;
+
+        if matches!(
+            stream.status,
+            StreamStatus::Completed
+                | StreamStatus::Cancelled
+                | StreamStatus::Settled
+                | StreamStatus::Unfunded
+        ) {
+            return Ok(0);
+        }
+
+        let accrued = Self::calculate_accrued(env, stream_id)?;
+        Ok(stream.deposit_amount - accrued)
+    }
+
+    /// Number of streams that have not reached a terminal status.
+    ///
+    /// Counts `Active`, `Paused`, `PendingAcceptance`, and `Unfunded` streams.
+    /// Maintained on every creation and every transition to `Completed` or `Cancelled`;
+    /// the admin can recompute it with `rebuild_counters`.
+    ///
+    /// # Usage Notes
+    /// - This is a view function (read-only, no state changes)
+    /// - No authorization required (public information)
+    pub fn get_active_stream_count(env: Env) -> u64 {
+        get_active_stream_count(&env)
+    }
+
+    /// Number of streams in each of the main statuses.
+    ///
+    /// Aggregate counts for dashboards, kept up to date on every status transition so
+    /// no scan is needed. `PendingAcceptance`, `Unfunded`, and `Settled` streams are not
+    /// part of any of the four counts. The admin can recompute them with `rebuild_counters`.
+    ///
+    /// # Returns
+    /// - `(u64, u64, u64, u64)`: `(active, paused, completed, cancelled)`
+    ///
+    /// # Usage Notes
+    /// - This is a view function (read-only, no state changes)
+    /// - No authorization required (public information)
+    pub fn get_status_counts(env: Env) -> (u64, u64, u64, u64) {
+        (
+            get_status_count(&env, StreamStatus::Active),
+            get_status_count(&env, StreamStatus::Paused),
+            get_status_count(&env, StreamStatus::Completed),
+            get_status_count(&env, StreamStatus::Cancelled),
+        )
+    }
+
+    /// Internal helper to check authorization for sender or admin.
+    fn require_sender_or_admin(env: &Env, sender: &Address) -> Result<(), ContractError> {
+        // Only the sender can manage their own stream via these paths.
+        // Admin overrides are handled by the 'as_admin' specific functions.
+        Self::require_party_auth(env, sender)
+    }
+
+    /// Internal helper requiring authorization from a stream party.
+    ///
+    /// Returns `ContractError::Unauthorized` when the party can never authorize
+    /// the call, so `try_` callers get a typed error instead of a host auth abort.
+    /// Missing or invalid signatures still fail inside `require_auth`.
+    fn require_party_auth(env: &Env, party: &Address) -> Result<(), ContractError> {
+        // The contract cannot sign for a call made to itself from outside.
+        if *party == env.current_contract_address() {
+            return Err(ContractError::Unauthorized);
+        }
+        party.require_auth();
+        Ok(())
+    }
+
+    /// Internal helper validating parameters, pulling the deposit from `payer`, and
+    /// storing a new stream owned by `sender`. `split_recipients` is set for split
+    /// streams, whose `recipient` is this contract.
+    #[allow(clippy::too_many_arguments)]
+    fn open_stream(
+        env: &Env,
+        payer: Address,
+        sender: Address,
+        recipient: Address,
+        deposit_amount: i128,
+        rate_per_second: i128,
+        start_time: u64,
+        cliff_time: u64,
+        end_time: u64,
+        options: CreateStreamOptions,
+        split_recipients: Option<&Vec<(Address, u32)>>,
+    ) -> u64 {
+        sender.require_auth();
+        if payer != sender {
+            payer.require_auth();
+        }
+        require_creator_permitted(env, &sender);
+        require_deposits_open(env);
+
+        assert!(
+            options.min_withdraw_amount >= 0,
+            "min_withdraw_amount must not be negative"
+        );
+        assert!(
+            options.security_deposit >= 0,
+            "security_deposit must not be negative"
+        );
+
+        // The creation fee comes out of the deposit; the rest must still fund the stream.
+        let (fee, deposit_amount) = split_creation_fee(env, deposit_amount);
+        // A single unlock releases the deposit itself, so there is no rate to honour.
+        let rate_per_second = match options.accrual_kind {
+            AccrualKind::SingleUnlock => 0,
+            _ => rate_per_second,
+        };
+
+        // A split stream is held by the contract itself, so its real recipients are
+        // the ones validated.
+        match split_recipients {
+            Some(recipients) => {
+                for (split_recipient, _) in recipients.iter() {
+                    Self::validate_stream_params(
+                        env,
+                        &sender,
+                        &split_recipient,
+                        deposit_amount,
+                        rate_per_second,
+                        start_time,
+                        cliff_time,
+                        end_time,
+                        &options.accrual_kind,
+                    );
+                }
+            }
+            None => Self::validate_stream_params(
+                env,
+                &sender,
+                &recipient,
+                deposit_amount,
+                rate_per_second,
+                start_time,
+                cliff_time,
+                end_time,
+                &options.accrual_kind,
+            ),
+        }
+        assert!(
+            options.cliff_unlock_amount >= 0,
+            "cliff_unlock_amount must not be negative"
+        );
+        assert!(
+            options.cliff_unlock_amount <= deposit_amount,
+            "cliff_unlock_amount must not exceed deposit_amount"
+        );
+        assert!(
+            options.max_withdrawable >= 0,
+            "max_withdrawable must not be negative"
+        );
+        assert!(
+            options.max_withdrawable <= deposit_amount,
+            "max_withdrawable must not exceed deposit_amount"
+        );
+        if let AccrualKind::FrontLoaded(curve) = &options.accrual_kind {
+            assert!(
+                curve.halflife_seconds > 0,
+                "halflife_seconds must be positive"
+            );
+        }
+        if let AccrualKind::Scheduled(mask) = &options.accrual_kind {
+            assert!(
+                mask.days <= 0x7f && mask.hours <= 0xff_ffff,
+                "schedule mask has bits outside the week"
+            );
+            assert!(
+                mask.days != 0 && mask.hours != 0,
+                "schedule mask must enable at least one hour"
+            );
+        }
+        require_start_not_too_far_in_past(env, start_time);
+
+        let end_time = if options.auto_extend {
+            assert!(
+                options.accrual_kind == AccrualKind::Linear,
+                "auto_extend requires linear accrual"
+            );
+            u64::try_from(deposit_amount / rate_per_second)
+                .ok()
+                .and_then(|duration| start_time.checked_add(duration))
+                .expect("overflow calculating extended end_time")
+        } else {
+            end_time
+        };
+
+        let token = get_token(env);
+        require_token_allowed(env, &token);
+
+        // Transfer tokens from payer to this contract (#36)
+        // If transfer fails (insufficient balance/allowance), this will panic
+        // and no state will be persisted (atomic transaction)
+        let token_client = token::Client::new(env, &token);
+        let funding = deposit_amount
+            .checked_add(options.security_deposit)
+            .expect("overflow calculating deposit plus security_deposit");
+        token_client.transfer(&payer, &env.current_contract_address(), &funding);
+        if fee > 0 {
+            token_client.transfer(&payer, &get_config(env).fee_collector, &fee);
+        }
+
+        // Only allocate stream id and persist state AFTER successful transfer
+        let status = if options.requires_acceptance {
+            StreamStatus::PendingAcceptance
+        } else {
+            StreamStatus::Active
+        };
+        Self::store_new_stream(
+            env,
+            sender,
+            recipient,
+            deposit_amount,
+            rate_per_second,
+            start_time,
+            cliff_time,
+            end_time,
+            &options,
+            status,
+        )
+    }
+
+    /// Internal helper transferring an `I256` total in as few transfers as possible, each
+    /// at most `i128::MAX` (the largest amount a token transfer accepts).
+    fn transfer_i256(
+        env: &Env,
+        token_client: &token::Client,
+        from: &Address,
+        to: &Address,
+        total: I256,
+    ) {
+        let max_transfer = I256::from_i128(env, i128::MAX);
+        let mut remaining = total;
+        while remaining > I256::from_i128(env, 0) {
+            let amount = if remaining > max_transfer {
+                i128::MAX
+            } else {
+                remaining.to_i128().expect("remaining transfer fits i128")
+            };
+            token_client.transfer(from, to, &amount);
+            remaining = remaining.sub(&I256::from_i128(env, amount));
+        }
+    }
+
+    /// Internal helper enforcing the creation constraints documented on `create_stream`.
+    /// `SingleUnlock` streams have no rate, so the rate checks are skipped for them.
+    #[allow(clippy::too_many_arguments)]
+    fn validate_stream_params(
+        env: &Env,
+        sender: &Address,
+        recipient: &Address,
+        deposit_amount: i128,
+        rate_per_second: i128,
+        start_time: u64,
+        cliff_time: u64,
+        end_time: u64,
+        accrual_kind: &AccrualKind,
+    ) {
+        let checked = Self::check_stream_params(
+            env,
+            sender,
+            recipient,
+            deposit_amount,
+            rate_per_second,
+            start_time,
+            cliff_time,
+            end_time,
+            accrual_kind,
+        );
+        match checked {
+            Ok(()) => {}
+            Err(InvalidParams::Message(message)) => panic!("{}", message),
+            Err(InvalidParams::Error(error)) => panic_with_error!(env, error),
+        }
+    }
+
+    /// The checks behind `validate_stream_params`, reporting the first failure instead
+    /// of panicking so batch creation can attribute it to an entry.
+    #[allow(clippy::too_many_arguments)]
+    fn check_stream_params(
+        env: &Env,
+        sender: &Address,
+        recipient: &Address,
+        deposit_amount: i128,
+        rate_per_second: i128,
+        start_time: u64,
+        cliff_time: u64,
+        end_time: u64,
+        accrual_kind: &AccrualKind,
+    ) -> Result<(), InvalidParams> {
+        let uses_rate = *accrual_kind != AccrualKind::SingleUnlock;
+
+        // Validate positive amounts (#35)
+        ensure(deposit_amount > 0, "deposit_amount must be positive")?;
+        if exceeds_deposit_cap(env, deposit_amount) {
+            return Err(InvalidParams::Error(ContractError::DepositExceedsCap));
+        }
+        ensure(
+            !uses_rate || rate_per_second > 0,
+            "rate_per_second must be positive",
+        )?;
 
         // Validate sender != recipient (#35)
-        assert!(
+        ensure(
             sender != recipient,
-            "sender and recipient must be different"
-        );
+            "sender and recipient must be different",
+        )?;
+        if is_invalid_recipient(env, recipient) {
+            return Err(InvalidParams::Error(ContractError::InvalidRecipient));
+        }
 
         // Validate time constraints
-        assert!(start_time < end_time, "start_time must be before end_time");
-        assert!(
+        ensure(start_time < end_time, "start_time must be before end_time")?;
+        if is_duration_too_short(env, start_time, end_time) {
+            return Err(InvalidParams::Error(ContractError::DurationTooShort));
+        }
+        if is_in_cancel_cooldown(env, sender) {
+            return Err(InvalidParams::Error(ContractError::CancelCooldown));
+        }
+        ensure(
             cliff_time >= start_time && cliff_time <= end_time,
-            "cliff_time must be within [start_time, end_time]"
-        );
+            "cliff_time must be within [start_time, end_time]",
+        )?;
 
         // Validate deposit covers total streamable amount (#34)
-        let duration = (end_time - start_time) as i128;
-        let total_streamable = rate_per_second
-            .checked_mul(duration)
-            .expect("overflow calculating total streamable amount");
-        assert!(
+        let total_streamable =
+            curve_total_streamable(accrual_kind, rate_per_second, start_time, end_time).ok_or(
+                InvalidParams::Message("overflow calculating total streamable amount"),
+            )?;
+        ensure(
             deposit_amount >= total_streamable,
-            "deposit_amount must cover total streamable amount (rate * duration)"
-        );
-
-        // Transfer tokens from sender to this contract (#36)
-        // If transfer fails (insufficient balance/allowance), this will panic
-        // and no state will be persisted (atomic transaction)
-        let token_client = token::Client::new(&env, &get_token(&env));
-        token_client.transfer(&sender, &env.current_contract_address(), &deposit_amount);
+            "deposit_amount must cover total streamable amount (rate * duration)",
+        )
+    }
 
-        // Only allocate stream id and persist state AFTER successful transfer
-        let stream_id = get_stream_count(&env);
-        set_stream_count(&env, stream_id + 1);
+    /// Internal helper allocating the next stream id and persisting a stream in `status`.
+    /// Every status but `Unfunded` means the deposit has already been transferred in.
+    #[allow(clippy::too_many_arguments)]
+    fn store_new_stream(
+        env: &Env,
+        sender: Address,
+        recipient: Address,
+        deposit_amount: i128,
+        rate_per_second: i128,
+        start_time: u64,
+        cliff_time: u64,
+        end_time: u64,
+        options: &CreateStreamOptions,
+        status: StreamStatus,
+    ) -> u64 {
+        let stream_id = get_stream_count(env);
+        let next_id = stream_id
+            .checked_add(1)
+            .unwrap_or_else(|| panic_with_error!(env, ContractError::StreamIdExhausted));
+        set_stream_count(env, next_id);
+        set_active_stream_count(env, get_active_stream_count(env) + 1);
+        // An unfunded stream holds no tokens until `fund_stream` pulls its deposit.
+        if status != StreamStatus::Unfunded {
+            add_to_total(
+                env,
+                DataKey::TotalDeposited,
+                deposit_amount + options.security_deposit,
+            );
+        }
 
         let stream = Stream {
             stream_id,
@@ -287,534 +4962,1159 @@ impl FluxoraStream {
             cliff_time,
             end_time,
             withdrawn_amount: 0,
-            status: StreamStatus::Active,
+            status,
             cancelled_at: None,
+            completed_at: None,
+            min_withdraw_interval: options.min_withdraw_interval,
+            last_withdraw_time: None,
+            min_withdraw_amount: options.min_withdraw_amount,
+            // The cliff bonus is accrued at `start_time` but stays hidden until the
+            // cliff, since nothing accrues before it.
+            accrued_checkpoint: options.cliff_unlock_amount,
+            checkpoint_time: start_time,
+            unclaimed_sweep_after: options.unclaimed_sweep_after,
+            swept_amount: 0,
+            cliff_unlock_amount: options.cliff_unlock_amount,
+            accrual_kind: options.accrual_kind.clone(),
+            last_updated: env.ledger().timestamp(),
+            cancel_effective_at: None,
+            max_withdrawable: match options.max_withdrawable {
+                0 => deposit_amount,
+                cap => cap,
+            },
+            pause_requested: false,
+            category: options.category.clone().unwrap_or(DEFAULT_CATEGORY),
+            paused_at: None,
+            on_withdraw_hook: None,
+            security_deposit: match status {
+                StreamStatus::Unfunded => 0,
+                _ => options.security_deposit,
+            },
+            created_at: env.ledger().timestamp(),
+        };
+
+        write_stream(env, &stream, None);
+        index_stream_category(env, &stream.category, stream_id);
+        index_stream_recipient(env, &stream.recipient, stream_id);
+        index_stream_sender(env, &stream.sender, stream_id);
+
+        // Both parties are topics so indexers can subscribe to one address's streams.
+        env.events().publish(
+            (symbol_short!("created"), stream.sender, stream.recipient),
+            (stream_id, deposit_amount),
+        );
+
+        stream_id
+    }
+
+    /// Cap a `PendingAcceptance` or `Unfunded` stream that is being cancelled at `0`
+    /// accrual.
+    ///
+    /// Such a stream never started, so its sender gets the whole deposit back. Its
+    /// accrual is frozen at `start_time`, where a cliff unlock or a single unlock
+    /// would otherwise already release tokens; clearing `max_withdrawable` makes every
+    /// curve accrue exactly `0` instead.
+    fn forfeit_unstarted_accrual(stream: &mut Stream) {
+        if matches!(
+            stream.status,
+            StreamStatus::PendingAcceptance | StreamStatus::Unfunded
+        ) {
+            stream.max_withdrawable = 0;
+        }
+    }
+
+    /// Timestamp at which a cancelled stream's accrual is frozen.
+    ///
+    /// A stream cancelled before acceptance never started, so it freezes at its
+    /// scheduled start where nothing has accrued. A pending `initiate_cancel` caps the
+    /// freeze at its `cancel_effective_at`.
+    fn accrual_freeze_time(env: &Env, stream: &Stream) -> u64 {
+        if matches!(
+            stream.status,
+            StreamStatus::PendingAcceptance | StreamStatus::Unfunded
+        ) {
+            stream.start_time
+        } else {
+            let now = env.ledger().timestamp();
+            stream.cancel_effective_at.map_or(now, |at| now.min(at))
+        }
+    }
+
+    /// Amount accrued by `now`, continuing from the stream's last checkpoint.
+    ///
+    /// Front-loaded streams are evaluated in closed form; their checkpoint is kept up
+    /// to date but not needed.
+    fn accrued_at(stream: &Stream, now: u64) -> i128 {
+        // A pending cancellation stops accrual at its effective time.
+        let now = stream.cancel_effective_at.map_or(now, |at| now.min(at));
+        // Nothing accrues before the schedule starts, e.g. when a scheduled stream is
+        // cancelled early. The curves already return 0 before the cliff, but this does
+        // not rely on `cliff_time >= start_time`.
+        if now < stream.start_time {
+            return 0;
+        }
+        let accrued = match &stream.accrual_kind {
+            AccrualKind::Linear => accrual::accrue_from_checkpoint(
+                stream.accrued_checkpoint,
+                stream.checkpoint_time,
+                stream.cliff_time,
+                stream.end_time,
+                stream.rate_per_second,
+                stream.deposit_amount,
+                now,
+            ),
+            AccrualKind::FrontLoaded(curve) => accrual::calculate_front_loaded_amount(
+                stream.start_time,
+                stream.cliff_time,
+                stream.end_time,
+                curve.halflife_seconds,
+                stream.cliff_unlock_amount,
+                stream.deposit_amount,
+                now,
+            ),
+            AccrualKind::SingleUnlock => accrual::calculate_single_unlock_amount(
+                stream.cliff_time,
+                stream.deposit_amount,
+                now,
+            ),
+            AccrualKind::Scheduled(mask) => accrual::accrue_scheduled_from_checkpoint(
+                stream.accrued_checkpoint,
+                stream.checkpoint_time,
+                stream.cliff_time,
+                stream.end_time,
+                stream.rate_per_second,
+                stream.deposit_amount,
+                mask.days,
+                mask.hours,
+                now,
+            ),
+        };
+        accrued.min(stream.max_withdrawable)
+    }
+
+    /// Mark a stream `Completed` once the recipient has everything it may ever accrue.
+    ///
+    /// Returns the buffer (`deposit_amount - max_withdrawable`) the caller must refund
+    /// to the sender after its own transfer. A cancelled stream got its buffer back with
+    /// the cancellation refund and already left the active count, so it returns `0`.
+    /// With `Config::settle_cancelled` enabled a cancelled stream is left for
+    /// `settle_if_drained` instead, even when it was cancelled at or after `end_time`.
+    fn complete_if_fully_withdrawn(env: &Env, stream: &mut Stream, now: u64) -> i128 {
+        if stream.withdrawn_amount != stream.max_withdrawable {
+            return 0;
+        }
+        if stream.status == StreamStatus::Cancelled && get_config(env).settle_cancelled {
+            return 0;
+        }
+        let buffer = if stream.status == StreamStatus::Cancelled {
+            0
+        } else {
+            decrement_active_stream_count(env);
+            stream.deposit_amount - stream.max_withdrawable
+        };
+        stream.status = StreamStatus::Completed;
+        stream.completed_at = Some(now);
+        buffer + take_security_deposit(stream)
+    }
+
+    /// Move a `Cancelled` stream to `Settled` once `accrued` has been fully withdrawn,
+    /// if `Config::settle_cancelled` is enabled.
+    fn settle_if_drained(env: &Env, stream: &mut Stream, accrued: i128) {
+        if stream.status == StreamStatus::Cancelled
+            && stream.withdrawn_amount == accrued
+            && get_config(env).settle_cancelled
+        {
+            stream.status = StreamStatus::Settled;
+        }
+    }
+
+    /// Set a cancelled stream's accrued but unwithdrawn amount aside for
+    /// `claim_cancelled`, counting it as withdrawn so `withdraw` cannot pay it twice.
+    ///
+    /// Split streams are refused with `InvalidState`: their recipient is this contract,
+    /// which can never claim, and their shares are paid by `withdraw_split`.
+    fn escrow_cancel_payout(env: &Env, mut stream: Stream) -> Result<(), ContractError> {
+        let stream_id = stream.stream_id;
+        if load_split_weights(env, stream_id).is_some() {
+            return Err(ContractError::InvalidState);
+        }
+        let accrued = Self::calculate_accrued(env.clone(), stream_id)?;
+        let owed = accrued - stream.withdrawn_amount;
+        if owed == 0 {
+            return Ok(());
+        }
+
+        stream.withdrawn_amount = accrued;
+        save_stream(env, &stream);
+        set_cancel_escrow(env, stream_id, owed);
+
+        env.events()
+            .publish((symbol_short!("escrowed"), stream_id), owed);
+        Ok(())
+    }
+
+    /// Publish `Settled(stream_id)` if the stream was just settled.
+    fn publish_settled(env: &Env, stream: &Stream) {
+        if stream.status == StreamStatus::Settled {
+            env.events().publish(
+                (symbol_short!("settled"), stream.stream_id),
+                StreamEvent::Settled(stream.stream_id),
+            );
+        }
+    }
+
+    /// Fold accrual up to the current time into the stream's checkpoint.
+    ///
+    /// Called by every operation that mutates a live stream so later accrual only has
+    /// to cover the time since the last mutation. Nothing is recorded before the cliff,
+    /// where accrual is still measured from `start_time`, or for streams that are not
+    /// `Active` or `Paused` (a pending stream has not started accruing).
+    fn checkpoint_accrual(env: &Env, stream: &mut Stream) {
+        if !matches!(stream.status, StreamStatus::Active | StreamStatus::Paused) {
+            return;
+        }
+        let now = env.ledger().timestamp();
+        if now < stream.cliff_time || now <= stream.checkpoint_time {
+            return;
+        }
+        stream.accrued_checkpoint = Self::accrued_at(stream, now);
+        stream.checkpoint_time = now.min(stream.end_time);
+    }
+
+    /// Refund the unstreamed deposit to `refund_to` (the sender when `None`) and mark the
+    /// stream `Cancelled` with accrual frozen at `cancelled_at`. A `reason` is published
+    /// with the event.
+    fn settle_cancellation(
+        env: &Env,
+        mut stream: Stream,
+        cancelled_at: u64,
+        reason: Option<u32>,
+        refund_to: Option<Address>,
+    ) -> Result<(), ContractError> {
+        let stream_id = stream.stream_id;
+        let accrued = Self::calculate_accrued(env.clone(), stream_id)?;
+        // An unfunded stream's deposit never left the sender, so there is nothing to refund.
+        let unstreamed = if stream.status == StreamStatus::Unfunded {
+            0
+        } else {
+            stream.deposit_amount - accrued
+        };
+
+        // CEI: write the final cancelled state once, before the external token transfer,
+        // so storage never holds a Cancelled stream without its `cancelled_at`.
+        Self::checkpoint_accrual(env, &mut stream);
+        Self::forfeit_unstarted_accrual(&mut stream);
+        let refund = unstreamed + take_security_deposit(&mut stream);
+        stream.status = StreamStatus::Cancelled;
+        stream.cancelled_at = Some(cancelled_at);
+        save_stream(env, &stream);
+        decrement_active_stream_count(env);
+
+        if refund > 0 {
+            refund_sender(env, &refund_to.unwrap_or(stream.sender), refund)?;
+        }
+
+        let event = match reason {
+            Some(reason) => {
+                StreamEvent::CancelledWithReason(CancelledWithReason { stream_id, reason })
+            }
+            None => StreamEvent::Cancelled(stream_id),
         };
+        env.events()
+            .publish((symbol_short!("cancelled"), stream_id), event);
+        Ok(())
+    }
+
+    /// Push a paused stream's schedule back by the time it has been paused at `now`.
+    ///
+    /// Returns `None` if a shifted time overflows `u64`; `stream` is then partly shifted.
+    fn shift_paused_schedule(stream: &mut Stream, now: u64) -> Option<()> {
+        let paused_duration = now.saturating_sub(stream.paused_at.unwrap_or(now));
+        stream.start_time = stream.start_time.checked_add(paused_duration)?;
+        stream.cliff_time = stream.cliff_time.checked_add(paused_duration)?;
+        stream.end_time = stream.end_time.checked_add(paused_duration)?;
+        stream.checkpoint_time = stream.checkpoint_time.checked_add(paused_duration)?;
+        Some(())
+    }
+
+    /// Move an `Active` stream to `Paused`, clearing any pause request.
+    fn pause_active_stream(env: &Env, mut stream: Stream) {
+        Self::checkpoint_accrual(env, &mut stream);
+        stream.status = StreamStatus::Paused;
+        stream.pause_requested = false;
+        stream.paused_at = Some(env.ledger().timestamp());
+        save_stream(env, &stream);
+
+        let stream_id = stream.stream_id;
+        env.events().publish(
+            (symbol_short!("paused"), stream_id),
+            StreamEvent::Paused(stream_id),
+        );
+    }
+
+    /// Pay the recipient everything accrued, refund the unstreamed deposit to the
+    /// sender, and mark the stream `Completed` at its vested amount.
+    fn settle_revocation(env: &Env, mut stream: Stream) -> Result<i128, ContractError> {
+        let stream_id = stream.stream_id;
+        if !matches!(stream.status, StreamStatus::Active | StreamStatus::Paused)
+            || load_split_weights(env, stream_id).is_some()
+        {
+            return Err(ContractError::InvalidState);
+        }
+
+        let accrued = Self::calculate_accrued(env.clone(), stream_id)?;
+        let payout = accrued - stream.withdrawn_amount;
+        let unstreamed = stream.deposit_amount - accrued;
+
+        // CEI: write the settled state before either external token transfer.
+        Self::checkpoint_accrual(env, &mut stream);
+        let refund = unstreamed + take_security_deposit(&mut stream);
+        stream.accrued_checkpoint = accrued;
+        stream.withdrawn_amount = accrued;
+        stream.max_withdrawable = accrued;
+        stream.status = StreamStatus::Completed;
+        stream.completed_at = Some(env.ledger().timestamp());
+        save_stream(env, &stream);
+        decrement_active_stream_count(env);
+
+        if payout > 0 {
+            pay_recipient(env, &stream.recipient, payout)?;
+        }
+        if refund > 0 {
+            refund_sender(env, &stream.sender, refund)?;
+        }
+
+        if payout > 0 {
+            env.events()
+                .publish((symbol_short!("withdrew"), stream_id), payout);
+        }
+        env.events().publish(
+            (symbol_short!("revoked"), stream_id),
+            StreamEvent::Revoked(stream_id),
+        );
+        env.events().publish(
+            (symbol_short!("completed"), stream_id),
+            StreamEvent::Completed(stream_id),
+        );
+        Ok(payout)
+    }
+
+    fn require_cancellable_status(env: &Env, status: StreamStatus) {
+        if status != StreamStatus::Active
+            && status != StreamStatus::Paused
+            && status != StreamStatus::PendingAcceptance
+            && status != StreamStatus::Unfunded
+        {
+            panic_with_error!(env, ContractError::InvalidState);
+        }
+    }
+}
+
+#[contractimpl]
+impl FluxoraStream {
+    /// Cancel a payment stream as the contract admin.
+    ///
+    /// Administrative override to cancel any stream, bypassing sender authorization.
+    /// Identical behavior to `cancel_stream` but requires admin authorization instead
+    /// of sender authorization. Useful for emergency interventions or dispute resolution.
+    ///
+    /// # Parameters
+    /// - `stream_id`: Unique identifier of the stream to cancel
+    ///
+    /// - `reason`: Optional operator-defined reason code, published with the event
+    ///
+    /// # Authorization
+    /// - Requires authorization from the contract admin (set during `init`)
+    ///
+    /// # Behavior
+    /// Same as `cancel_stream`:
+    /// 1. Validates stream is in `Active` or `Paused` state
+    /// 2. Calculates accrued amount based on time elapsed
+    /// 3. Refunds unstreamed tokens to sender
+    /// 4. Sets stream status to `Cancelled` and records `cancelled_at`
+    /// 5. Accrued amount remains for recipient to withdraw
+    ///
+    /// # Panics
+    /// - If stream is not `Active` or `Paused`
+    /// - If the stream does not exist
+    /// - If caller is not the admin
+    ///
+    /// # Errors
+    /// - `ContractError::InsufficientContractBalance` if the contract cannot cover the refund
+    ///
+    /// # Events
+    /// - Publishes `Cancelled(stream_id)` on success, or
+    ///   `CancelledWithReason { stream_id, reason }` when a reason is given
+    ///
+    /// # Usage Notes
+    /// - Admin can cancel any stream regardless of sender
+    /// - Use for emergency situations or dispute resolution
+    /// - Sender still receives refund of unstreamed tokens
+    /// - Recipient can still withdraw accrued amount
+    pub fn cancel_stream_as_admin(
+        env: Env,
+        stream_id: u64,
+        reason: Option<u32>,
+    ) -> Result<(), ContractError> {
+        let admin = get_admin(&env);
+        admin.require_auth();
+
+        let stream = load_stream(&env, stream_id)?;
+
+        assert!(
+            stream.status == StreamStatus::Active || stream.status == StreamStatus::Paused,
+            "stream must be active or paused to cancel"
+        );
+
+        let cancelled_at = Self::accrual_freeze_time(&env, &stream);
+        Self::settle_cancellation(&env, stream, cancelled_at, reason, None)
+    }
+
+    /// Immediately vest a stream's entire deposit to the recipient as the contract admin.
+    ///
+    /// Dispute-resolution override: accrual is treated as complete, everything not yet
+    /// withdrawn is paid to the recipient, and the stream becomes `Completed`. The
+    /// sender receives no refund.
+    ///
+    /// # Parameters
+    /// - `stream_id`: Unique identifier of the stream to complete
+    ///
+    /// # Returns
+    /// - `i128`: The amount transferred to the recipient (`deposit_amount - withdrawn_amount`)
+    ///
+    /// # Authorization
+    /// - Requires authorization from the contract admin
+    ///
+    /// # Errors
+    /// - `ContractError::StreamNotFound` if the stream does not exist
+    /// - `ContractError::InvalidState` if the stream is already `Completed` or
+    ///   `Cancelled`, is still `Unfunded`, or is a split stream (whose recipients claim
+    ///   via `withdraw_split`)
+    /// - `ContractError::InsufficientContractBalance` if the contract cannot cover the payout
+    ///
+    /// # Events
+    /// - Publishes `force_completed(stream_id, amount)` followed by `Completed(stream_id)`
+    pub fn force_complete_as_admin(env: Env, stream_id: u64) -> Result<i128, ContractError> {
+        get_admin(&env).require_auth();
+
+        let mut stream = load_stream(&env, stream_id)?;
+        if matches!(
+            stream.status,
+            StreamStatus::Completed
+                | StreamStatus::Cancelled
+                | StreamStatus::Settled
+                | StreamStatus::Unfunded
+        ) || load_split_weights(&env, stream_id).is_some()
+        {
+            return Err(ContractError::InvalidState);
+        }
+
+        let now = env.ledger().timestamp();
+        let amount = stream.max_withdrawable - stream.withdrawn_amount;
+        let buffer =
+            stream.deposit_amount - stream.max_withdrawable + take_security_deposit(&mut stream);
+
+        // CEI: update state before external token transfer to reduce reentrancy risk.
+        stream.accrued_checkpoint = stream.max_withdrawable;
+        stream.checkpoint_time = now;
+        stream.withdrawn_amount = stream.max_withdrawable;
+        stream.status = StreamStatus::Completed;
+        stream.completed_at = Some(now);
+        save_stream(&env, &stream);
+        decrement_active_stream_count(&env);
+
+        if amount > 0 {
+            pay_recipient(&env, &stream.recipient, amount)?;
+        }
+        if buffer > 0 {
+            refund_sender(&env, &stream.sender, buffer)?;
+        }
+
+        env.events()
+            .publish((Symbol::new(&env, "force_completed"), stream_id), amount);
+        env.events().publish(
+            (symbol_short!("completed"), stream_id),
+            StreamEvent::Completed(stream_id),
+        );
+        Ok(amount)
+    }
+
+    /// Pay a stream's held security deposit to its recipient after a breach.
+    ///
+    /// The deposit otherwise goes back to the sender when the stream completes or is
+    /// cancelled, so this must be called while the stream is still live. Streaming
+    /// itself is unaffected.
+    ///
+    /// # Returns
+    /// - `i128`: The amount paid to the recipient
+    ///
+    /// # Authorization
+    /// - Requires authorization from the contract admin
+    ///
+    /// # Errors
+    /// - `ContractError::StreamNotFound` if the stream does not exist
+    /// - `ContractError::InvalidState` if no security deposit is held
+    /// - `ContractError::InsufficientContractBalance` if the contract cannot cover it
+    ///
+    /// # Events
+    /// - Publishes `forfeited(stream_id, amount)` event on success
+    pub fn forfeit_deposit_as_admin(env: Env, stream_id: u64) -> Result<i128, ContractError> {
+        get_admin(&env).require_auth();
+
+        let mut stream = load_stream(&env, stream_id)?;
+        let amount = take_security_deposit(&mut stream);
+        if amount == 0 {
+            return Err(ContractError::InvalidState);
+        }
+
+        // CEI: update state before external token transfer to reduce reentrancy risk.
+        save_stream(&env, &stream);
+        pay_recipient(&env, &stream.recipient, amount)?;
+
+        env.events()
+            .publish((symbol_short!("forfeited"), stream_id), amount);
+        Ok(amount)
+    }
+
+    /// Revoke a stream as the contract admin.
+    ///
+    /// Same behaviour as `revoke_stream` (vested amount to the recipient, the rest to
+    /// the sender, stream `Completed`), with admin authorization instead of the
+    /// sender's.
+    ///
+    /// # Authorization
+    /// - Requires authorization from the contract admin
+    ///
+    /// # Errors
+    /// - Same as `revoke_stream`
+    pub fn revoke_stream_as_admin(env: Env, stream_id: u64) -> Result<i128, ContractError> {
+        get_admin(&env).require_auth();
+        let stream = load_stream(&env, stream_id)?;
+        Self::settle_revocation(&env, stream)
+    }
+
+    /// Add a token to the whitelist of tokens streams may be created with.
+    ///
+    /// While the whitelist is empty only the config token is accepted. Once any token
+    /// is whitelisted, `create_stream` accepts exactly the whitelisted set, so the
+    /// config token must be added explicitly to keep using it.
+    ///
+    /// # Parameters
+    /// - `token`: Token contract address to allow
+    ///
+    /// # Authorization
+    /// - Requires authorization from the contract admin
+    ///
+    /// # Events
+    /// - Publishes `token_allowed(token)` event when the token was not already allowed
+    ///
+    /// # Usage Notes
+    /// - Adding an already-allowed token is a no-op
+    pub fn add_allowed_token(env: Env, token: Address) {
+        get_admin(&env).require_auth();
 
-        save_stream(&env, &stream);
+        if is_allowed_token(&env, &token) {
+            return;
+        }
 
-        env.events()
-            .publish((symbol_short!("created"), stream_id), deposit_amount);
+        let key = DataKey::AllowedToken(token.clone());
+        env.storage().persistent().set(&key, &true);
+        env.storage().persistent().extend_ttl(&key, 17280, 120960);
+        set_allowed_token_count(&env, get_allowed_token_count(&env) + 1);
 
-        stream_id
+        env.events()
+            .publish((symbol_short!("token"), symbol_short!("allowed")), token);
     }
 
-    /// Pause an active payment stream.
+    /// Remove a token from the creation whitelist.
     ///
-    /// Temporarily halts withdrawals from the stream while preserving accrual calculations.
-    /// The stream can be resumed later by the sender or admin. Accrual continues based on
-    /// time elapsed, but the recipient cannot withdraw while paused.
+    /// Existing streams are unaffected; only new creations are checked. Removing the
+    /// last whitelisted token restores the default of accepting the config token only.
     ///
     /// # Parameters
-    /// - `stream_id`: Unique identifier of the stream to pause
+    /// - `token`: Token contract address to disallow
     ///
     /// # Authorization
-    /// - Requires authorization from the stream's sender (original creator)
-    /// - Admin can use `pause_stream_as_admin` for administrative override
-    ///
-    /// # Panics
-    /// - If the stream is not in `Active` state (already paused, completed, or cancelled)
-    /// - If the stream does not exist (`stream_id` is invalid)
-    /// - If caller is not authorized (not the sender)
+    /// - Requires authorization from the contract admin
     ///
     /// # Events
-    /// - Publishes `Paused(stream_id)` event on success
+    /// - Publishes `token_removed(token)` event when the token was allowed
     ///
     /// # Usage Notes
-    /// - Pausing does not affect accrual calculations (time-based)
-    /// - Recipient cannot withdraw while stream is paused
-    /// - Stream can be cancelled while paused
-    /// - Use `resume_stream` to reactivate withdrawals
-    pub fn pause_stream(env: Env, stream_id: u64) -> Result<(), ContractError> {
-        let mut stream = load_stream(&env, stream_id)?;
-
-        Self::require_sender_or_admin(&env, &stream.sender);
+    /// - Removing a token that is not allowed is a no-op
+    pub fn remove_allowed_token(env: Env, token: Address) {
+        get_admin(&env).require_auth();
 
-        if stream.status == StreamStatus::Paused {
-            panic!("stream is already paused");
+        if !is_allowed_token(&env, &token) {
+            return;
         }
 
-        assert!(
-            stream.status == StreamStatus::Active,
-            "stream must be active to pause"
-        );
+        env.storage()
+            .persistent()
+            .remove(&DataKey::AllowedToken(token.clone()));
+        set_allowed_token_count(&env, get_allowed_token_count(&env) - 1);
 
-        stream.status = StreamStatus::Paused;
-        save_stream(&env, &stream);
+        env.events()
+            .publish((symbol_short!("token"), symbol_short!("removed")), token);
+    }
+
+    /// Allow a sender to create streams while the creator allowlist is enabled.
+    ///
+    /// The list only matters once `set_creators_allowlist(true)` is called; it can be
+    /// filled beforehand.
+    ///
+    /// # Parameters
+    /// - `creator`: Sender address to allow
+    ///
+    /// # Authorization
+    /// - Requires authorization from the contract admin
+    ///
+    /// # Events
+    /// - Publishes `creator_allowed(creator)` event when the sender was not already allowed
+    ///
+    /// # Usage Notes
+    /// - Adding an already-allowed sender is a no-op
+    pub fn add_allowed_creator(env: Env, creator: Address) {
+        get_admin(&env).require_auth();
+
+        if is_allowed_creator(&env, &creator) {
+            return;
+        }
+
+        let key = DataKey::AllowedCreator(creator.clone());
+        env.storage().persistent().set(&key, &true);
+        env.storage().persistent().extend_ttl(&key, 17280, 120960);
 
         env.events().publish(
-            (symbol_short!("paused"), stream_id),
-            StreamEvent::Paused(stream_id),
+            (symbol_short!("creator"), symbol_short!("allowed")),
+            creator,
         );
-        Ok(())
     }
 
-    /// Resume a paused payment stream.
+    /// Remove a sender from the creator allowlist.
     ///
-    /// Reactivates a paused stream, allowing the recipient to withdraw accrued funds again.
-    /// Only streams in `Paused` state can be resumed. Terminal states (Completed, Cancelled)
-    /// cannot be resumed.
+    /// The sender's existing streams are unaffected; only new creations are checked.
     ///
     /// # Parameters
-    /// - `stream_id`: Unique identifier of the stream to resume
+    /// - `creator`: Sender address to disallow
     ///
     /// # Authorization
-    /// - Requires authorization from the stream's sender (original creator)
-    /// - Admin can use `resume_stream_as_admin` for administrative override
-    ///
-    /// # Panics
-    /// - If the stream is `Active` (not paused, already running)
-    /// - If the stream is `Completed` (terminal state, cannot be resumed)
-    /// - If the stream is `Cancelled` (terminal state, cannot be resumed)
-    /// - If the stream does not exist (`stream_id` is invalid)
-    /// - If caller is not authorized (not the sender)
+    /// - Requires authorization from the contract admin
     ///
     /// # Events
-    /// - Publishes `Resumed(stream_id)` event on success
+    /// - Publishes `creator_removed(creator)` event when the sender was allowed
     ///
     /// # Usage Notes
-    /// - Only paused streams can be resumed
-    /// - Accrual calculations are time-based and unaffected by pause/resume
-    /// - After resume, recipient can immediately withdraw accrued funds
-    pub fn resume_stream(env: Env, stream_id: u64) -> Result<(), ContractError> {
-        let mut stream = load_stream(&env, stream_id)?;
-        Self::require_sender_or_admin(&env, &stream.sender);
+    /// - Removing a sender that is not allowed is a no-op
+    pub fn remove_allowed_creator(env: Env, creator: Address) {
+        get_admin(&env).require_auth();
 
-        match stream.status {
-            StreamStatus::Active => panic!("stream is active, not paused"),
-            StreamStatus::Completed => panic!("stream is completed"),
-            StreamStatus::Cancelled => panic!("stream is cancelled"),
-            StreamStatus::Paused => {}
+        if !is_allowed_creator(&env, &creator) {
+            return;
         }
 
-        stream.status = StreamStatus::Active;
-        save_stream(&env, &stream);
+        env.storage()
+            .persistent()
+            .remove(&DataKey::AllowedCreator(creator.clone()));
 
         env.events().publish(
-            (symbol_short!("resumed"), stream_id),
-            StreamEvent::Resumed(stream_id),
+            (symbol_short!("creator"), symbol_short!("removed")),
+            creator,
         );
-        Ok(())
     }
 
-    /// Cancel a payment stream and refund unstreamed funds to the sender.
+    /// Whether `creator` is on the creator allowlist (regardless of whether the
+    /// allowlist is enabled).
+    pub fn is_allowed_creator(env: Env, creator: Address) -> bool {
+        is_allowed_creator(&env, &creator)
+    }
+
+    /// Set how far in the past a new stream's `start_time` may lie.
     ///
-    /// Terminates an active or paused stream, immediately refunding any unstreamed tokens
-    /// to the sender. The accrued amount (based on time elapsed) remains in the contract
-    /// for the recipient to withdraw. This is a terminal operation - cancelled streams
-    /// cannot be resumed.
+    /// Creations with `start_time + max_backdating_seconds < now` are rejected with
+    /// `ContractError::StartTooFarInPast`, catching streams that would unlock most of
+    /// their deposit at once because the sender meant "now". Existing streams are
+    /// unaffected.
     ///
     /// # Parameters
-    /// - `stream_id`: Unique identifier of the stream to cancel
+    /// - `max_backdating_seconds`: Allowed backdating in seconds; `0` (the default)
+    ///   means unlimited
     ///
     /// # Authorization
-    /// - Requires authorization from the stream's sender (original creator)
-    /// - Admin can use `cancel_stream_as_admin` for administrative override
+    /// - Requires authorization from the contract admin
     ///
-    /// # Behavior
-    /// 1. Validates stream is in `Active` or `Paused` state
-    /// 2. Calculates accrued amount: `min((now - start_time) × rate, deposit_amount)`
-    /// 3. Calculates refund: `deposit_amount - accrued`
-    /// 4. Transfers refund to sender (if > 0)
-    /// 5. Sets stream status to `Cancelled`
-    /// 6. Accrued but not withdrawn amount remains for recipient
+    /// # Events
+    /// - Publishes `(config, backdate)` with the new limit
+    pub fn set_max_backdating(env: Env, max_backdating_seconds: u64) {
+        get_admin(&env).require_auth();
+
+        env.storage()
+            .instance()
+            .set(&DataKey::MaxBackdating, &max_backdating_seconds);
+
+        env.events().publish(
+            (symbol_short!("config"), symbol_short!("backdate")),
+            max_backdating_seconds,
+        );
+    }
+
+    /// Current `max_backdating_seconds` limit for new streams (`0` = unlimited).
+    pub fn get_max_backdating(env: Env) -> u64 {
+        get_max_backdating(&env)
+    }
+
+    /// Set where `sweep_stray_tokens` sends recovered tokens.
     ///
-    /// # Returns
-    /// - Implicitly returns via state change and token transfer
+    /// # Parameters
+    /// - `treasury`: New sweep destination; until one is set, the admin receives sweeps
+    ///
+    /// # Authorization
+    /// - Requires authorization from the contract admin
+    ///
+    /// # Events
+    /// - Publishes `(config, treasury)` with the new address
+    pub fn set_treasury(env: Env, treasury: Address) {
+        get_admin(&env).require_auth();
+
+        env.storage().instance().set(&DataKey::Treasury, &treasury);
+
+        env.events().publish(
+            (symbol_short!("config"), symbol_short!("treasury")),
+            treasury,
+        );
+    }
+
+    /// Current destination of `sweep_stray_tokens` (the admin unless one was set).
+    pub fn get_treasury(env: Env) -> Address {
+        get_treasury(&env)
+    }
+
+    /// Recover tokens sent to the contract directly rather than through a stream.
+    ///
+    /// For the stream token only the balance above the tracked stream obligations
+    /// (`TotalDeposited - TotalWithdrawn - TotalRefunded`) is stray; any other token is
+    /// stray in full, since streams only ever hold the stream token.
+    ///
+    /// # Parameters
+    /// - `token`: Token contract to sweep
+    /// - `amount`: Amount to send to the treasury (must be > 0)
+    ///
+    /// # Authorization
+    /// - Requires authorization from the contract admin
+    ///
+    /// # Errors
+    /// - `ContractError::SweepExceedsStray` if `amount` is more than the stray balance
     ///
     /// # Panics
-    /// - If stream is not `Active` or `Paused` (already completed or cancelled)
-    /// - If the stream does not exist (`stream_id` is invalid)
-    /// - If caller is not authorized (not the sender)
-    /// - If token transfer fails (should not happen with valid contract state)
+    /// - If `amount` is not positive
     ///
     /// # Events
-    /// - Publishes `Cancelled(stream_id)` event on success
+    /// - Publishes `(stray_swept, token)` with `(treasury, amount)`
     ///
     /// # Usage Notes
-    /// - Cancellation is irreversible (terminal state)
-    /// - Recipient can still withdraw accrued amount after cancellation
-    /// - If fully accrued (time >= end_time), sender receives no refund
-    /// - Accrual is time-based, not affected by pause state
-    /// - Can be called on paused streams
-    ///
-    /// # Examples
-    /// - Cancel at 30% completion → sender gets 70% refund, recipient can withdraw 30%
-    /// - Cancel at 100% completion → sender gets 0% refund, recipient can withdraw 100%
-    /// - Cancel before cliff → sender gets 100% refund (no accrual before cliff)
-    pub fn cancel_stream(env: Env, stream_id: u64) -> Result<(), ContractError> {
-        let mut stream = load_stream(&env, stream_id)?;
-        Self::require_sender_or_admin(&env, &stream.sender);
-        Self::require_cancellable_status(&env, stream.status);
-
-        let accrued = Self::calculate_accrued(env.clone(), stream_id)?;
-        let unstreamed = stream.deposit_amount - accrued;
-
-        // CEI: update state before external token transfer to reduce reentrancy risk.
-        stream.status = StreamStatus::Cancelled;
-        save_stream(&env, &stream);
+    /// - The totals start at zero when the contract is deployed; streams created by an
+    ///   earlier version without them are not counted as obligations
+    pub fn sweep_stray_tokens(env: Env, token: Address, amount: i128) -> Result<(), ContractError> {
+        get_admin(&env).require_auth();
+        assert!(amount > 0, "amount must be positive");
 
-        if unstreamed > 0 {
-            let token_client = token::Client::new(&env, &get_token(&env));
-            token_client.transfer(&env.current_contract_address(), &stream.sender, &unstreamed);
+        let token_client = token::Client::new(&env, &token);
+        let contract = env.current_contract_address();
+        let balance = token_client.balance(&contract);
+        let stray = if token == get_token(&env) {
+            I256::from_i128(&env, balance).sub(&get_stream_obligations(&env))
+        } else {
+            I256::from_i128(&env, balance)
+        };
+        if I256::from_i128(&env, amount) > stray {
+            return Err(ContractError::SweepExceedsStray);
         }
 
-        stream.status = StreamStatus::Cancelled;
-        stream.cancelled_at = Some(env.ledger().timestamp());
-        save_stream(&env, &stream);
+        let treasury = get_treasury(&env);
+        token_client.transfer(&contract, &treasury, &amount);
 
         env.events().publish(
-            (symbol_short!("cancelled"), stream_id),
-            StreamEvent::Cancelled(stream_id),
+            (Symbol::new(&env, "stray_swept"), token),
+            (treasury, amount),
         );
         Ok(())
     }
 
-    /// Withdraw accrued tokens from a payment stream to the recipient.
+    /// The contract's current balance of `token`, or of the config token when `None`.
     ///
-    /// Transfers all accrued-but-not-yet-withdrawn tokens to the stream's recipient.
-    /// The amount withdrawn is calculated as `accrued - withdrawn_amount`, where accrued
-    /// is based on time elapsed since stream start. If this withdrawal completes the
-    /// stream (all deposited tokens withdrawn), the stream status transitions to `Completed`.
+    /// Lets reconciliation tooling compare holdings with stream obligations in one
+    /// call without looking up the token address first.
     ///
-    /// # Parameters
-    /// - `stream_id`: Unique identifier of the stream to withdraw from
+    /// # Panics
+    /// - If `token` is `None` and the contract has not been initialized
+    pub fn get_contract_balance(env: Env, token: Option<Address>) -> i128 {
+        let token = token.unwrap_or_else(|| get_token(&env));
+        token::Client::new(&env, &token).balance(&env.current_contract_address())
+    }
+
+    /// Set the origination fee taken from every new stream's deposit.
     ///
-    /// # Returns
-    /// - `i128`: The amount of tokens transferred to the recipient
+    /// On creation, `deposit_amount * creation_fee_bps / 10000` (rounded per `rounding_mode`) goes to
+    /// `fee_collector` and the rest funds the stream, which must still cover
+    /// `rate_per_second * (end_time - start_time)`. Existing streams are unaffected.
+    ///
+    /// # Parameters
+    /// - `creation_fee_bps`: Fee in basis points; `0` (the default) disables the fee
+    /// - `fee_collector`: Address receiving creation fees
     ///
     /// # Authorization
-    /// - Requires authorization from the stream's recipient (only recipient can withdraw)
-    /// - This prevents anyone from withdrawing on behalf of the recipient
+    /// - Requires authorization from the contract admin
     ///
     /// # Panics
-    /// - If the stream is `Completed` (all tokens already withdrawn)
-    /// - If the stream is `Paused` (withdrawals not allowed while paused)
-    /// - If there is nothing to withdraw (`accrued == withdrawn_amount`)
-    /// - If the stream does not exist (`stream_id` is invalid)
-    /// - If caller is not authorized (not the recipient)
-    /// - If token transfer fails (insufficient contract balance, should not happen)
-    ///
-    /// # State Changes
-    /// - Updates `withdrawn_amount` by the amount transferred
-    /// - Sets status to `Completed` if all deposited tokens are withdrawn
-    /// - Extends stream storage TTL to prevent expiration
+    /// - If `creation_fee_bps` exceeds 10000
     ///
     /// # Events
-    /// - Publishes `withdrew(stream_id, amount)` event on success
-    ///
-    /// # Usage Notes
-    /// - Can be called multiple times to withdraw incrementally
-    /// - Accrual is time-based: `min((now - start_time) × rate, deposit_amount)`
-    /// - Before cliff time, accrued amount is 0 (nothing to withdraw)
-    /// - After end_time, accrued amount is capped at deposit_amount
-    /// - Works on `Active` and `Cancelled` streams, not on `Paused` or `Completed`
-    /// - For cancelled streams, only the accrued amount (not refunded) can be withdrawn
-    ///
-    /// # Examples
-    /// - Stream: 1000 tokens over 1000 seconds (1 token/sec)
-    /// - At t=300: withdraw() returns 300 tokens
-    /// - At t=800: withdraw() returns 500 tokens (800 - 300 already withdrawn)
-    /// - At t=1000: withdraw() returns 200 tokens, status → Completed
-    pub fn withdraw(env: Env, stream_id: u64) -> Result<i128, ContractError> {
-        let mut stream = load_stream(&env, stream_id)?;
+    /// - Publishes `(config, fee)` with `(creation_fee_bps, fee_collector)`
+    pub fn set_creation_fee(env: Env, creation_fee_bps: u32, fee_collector: Address) {
+        let mut config = get_config(&env);
+        config.admin.require_auth();
+        assert!(
+            creation_fee_bps <= 10_000,
+            "creation_fee_bps must not exceed 10000"
+        );
 
-        // Enforce recipient-only authorization: only the stream's recipient can withdraw
-        // This is equivalent to checking env.invoker() == stream.recipient
-        // require_auth() ensures only the recipient can authorize this call,
-        // preventing anyone from withdrawing on behalf of the recipient
-        stream.recipient.require_auth();
+        config.creation_fee_bps = creation_fee_bps;
+        config.fee_collector = fee_collector.clone();
+        set_config(&env, &config);
 
-        assert!(
-            stream.status != StreamStatus::Completed,
-            "stream already completed"
+        env.events().publish(
+            (symbol_short!("config"), symbol_short!("fee")),
+            (creation_fee_bps, fee_collector),
         );
+    }
 
-        assert!(
-            stream.status != StreamStatus::Paused,
-            "cannot withdraw from paused stream"
+    /// Set the largest deposit a single stream may hold.
+    ///
+    /// Creations whose (post-fee) deposit is above the cap fail with
+    /// `ContractError::DepositExceedsCap`, as do extensions that would take a stream
+    /// above it. Existing streams are unaffected.
+    ///
+    /// # Parameters
+    /// - `max_deposit`: Per-stream ceiling; `0` (the default) means unlimited
+    ///
+    /// # Authorization
+    /// - Requires authorization from the contract admin
+    ///
+    /// # Panics
+    /// - If `max_deposit` is negative
+    ///
+    /// # Events
+    /// - Publishes `(config, max_dep)` with the new cap
+    pub fn set_max_deposit(env: Env, max_deposit: i128) {
+        let mut config = get_config(&env);
+        config.admin.require_auth();
+        assert!(max_deposit >= 0, "max_deposit must not be negative");
+
+        config.max_deposit = max_deposit;
+        set_config(&env, &config);
+
+        env.events().publish(
+            (symbol_short!("config"), symbol_short!("max_dep")),
+            max_deposit,
         );
+    }
 
-        let accrued = Self::calculate_accrued(env.clone(), stream_id)?;
-        let withdrawable = accrued - stream.withdrawn_amount;
-        assert!(withdrawable > 0, "nothing to withdraw");
+    /// Set how inexact divisions are rounded.
+    ///
+    /// Applies to rates derived by `create_stream_rate_per_period` and to creation fees,
+    /// for streams created afterwards. Accrual itself is not affected: linear accrual
+    /// involves no division, and the front-loaded curve always rounds down so that
+    /// switching modes can never take back an amount already withdrawn.
+    ///
+    /// # Parameters
+    /// - `rounding_mode`: `Floor` (the default), `Ceil`, or `Nearest`
+    ///
+    /// # Authorization
+    /// - Requires authorization from the contract admin
+    ///
+    /// # Events
+    /// - Publishes `(config, rounding)` with the new mode
+    pub fn set_rounding_mode(env: Env, rounding_mode: RoundingMode) {
+        let mut config = get_config(&env);
+        config.admin.require_auth();
 
-        // CEI: update state before external token transfer to reduce reentrancy risk.
-        stream.withdrawn_amount += withdrawable;
-        if stream.withdrawn_amount == stream.deposit_amount {
-            stream.status = StreamStatus::Completed;
-        }
-        save_stream(&env, &stream);
+        config.rounding_mode = rounding_mode;
+        set_config(&env, &config);
 
-        let token_client = token::Client::new(&env, &get_token(&env));
-        token_client.transfer(
-            &env.current_contract_address(),
-            &stream.recipient,
-            &withdrawable,
+        env.events().publish(
+            (symbol_short!("config"), symbol_short!("rounding")),
+            rounding_mode,
         );
+    }
+
+    /// Set the shortest duration a new stream may have.
+    ///
+    /// Creations whose `end_time - start_time` is below the minimum fail with
+    /// `ContractError::DurationTooShort`, which keeps throwaway streams from bloating
+    /// storage. Existing streams are unaffected.
+    ///
+    /// # Parameters
+    /// - `min_duration_seconds`: Minimum duration; `0` (the default) means no minimum
+    ///
+    /// # Authorization
+    /// - Requires authorization from the contract admin
+    ///
+    /// # Events
+    /// - Publishes `(config, min_dur)` with the new minimum
+    pub fn set_min_duration(env: Env, min_duration_seconds: u64) {
+        let mut config = get_config(&env);
+        config.admin.require_auth();
 
-        env.events()
-            .publish((symbol_short!("withdrew"), stream_id), withdrawable);
-        Ok(withdrawable)
+        config.min_duration_seconds = min_duration_seconds;
+        set_config(&env, &config);
+
+        env.events().publish(
+            (symbol_short!("config"), symbol_short!("min_dur")),
+            min_duration_seconds,
+        );
     }
 
-    /// Calculate the total amount accrued to the recipient at the current time.
+    /// Switch the token new streams are created in, e.g. after a token upgrade.
     ///
-    /// # Behaviour by status
+    /// Every stream is paid out in the configured token, so the switch is only
+    /// allowed while no stream is `Active`, `Paused`, `PendingAcceptance`, or
+    /// `Unfunded` (the active-stream counter is zero); otherwise their deposits would
+    /// be stranded in the old token. Accrual left unclaimed on `Cancelled` streams is
+    /// paid in the new token, so recipients should withdraw it first.
     ///
-    /// | Status      | Return value                                         |
-    /// |-------------|------------------------------------------------------|
-    /// | `Active`    | `min((now - start) × rate, deposit_amount)`          |
-    /// | `Paused`    | Same time-based formula (accrual is not paused)      |
-    /// | `Completed` | `deposit_amount` — all tokens were accrued/withdrawn |
-    /// | `Cancelled` | Final accrued at cancellation timestamp (frozen value) |
+    /// # Parameters
+    /// - `new_token`: Token contract for all future streams and payouts
     ///
-    /// ## Rationale for `Cancelled`
-    /// On cancellation, unstreamed tokens are refunded immediately to the sender.
-    /// The recipient can claim only what was already accrued at cancellation time.
-    /// Returning a frozen final accrued value keeps `calculate_accrued` consistent
-    /// with contract balances and prevents post-cancel time growth.
+    /// # Authorization
+    /// - Requires authorization from the contract admin
     ///
-    /// # Calculation
-    /// - Before `cliff_time`: returns 0 (no accrual before cliff)
-    /// - After `cliff_time`: `min((now - start_time) × rate_per_second, deposit_amount)`
-    /// - After `end_time`: capped at `deposit_amount` (no accrual beyond end)
+    /// # Errors
+    /// - `ContractError::TokenInUse` if any stream is not yet `Completed` or `Cancelled`
+    /// - `ContractError::InvalidToken` if `new_token` does not answer `decimals()`
     ///
-    /// # Panics
-    /// - If the stream does not exist (`stream_id` is invalid)
+    /// # Events
+    /// - Publishes `(config, token)` with the new token
     ///
     /// # Usage Notes
-    /// - This is a view function (read-only, no state changes)
-    /// - No authorization required (public information)
-    /// - Returns total accrued, not withdrawable amount
-    /// - To get withdrawable amount: `calculate_accrued() - stream.withdrawn_amount`
-    /// - Active/Paused streams accrue by current time; Completed/Cancelled are deterministic
-    /// - Useful for UIs to show real-time accrual without transactions
-    ///
-    /// # Examples
-    /// - Stream: 1000 tokens, 0-1000s, rate 1 token/sec, cliff at 500s
-    /// - At t=300: returns 0 (before cliff)
-    /// - At t=500: returns 500 (at cliff, accrual from start_time)
-    /// - At t=800: returns 800
-    /// - At t=1500: returns 1000 (capped at deposit_amount)
-    /// ## Rationale for `Completed`
-    /// When a stream reaches `Completed`, `withdrawn_amount == deposit_amount`.
-    /// There is no further accrual possible. Returning `deposit_amount` is the
-    /// deterministic, timestamp-independent answer for any UI or downstream caller.
-    pub fn calculate_accrued(env: Env, stream_id: u64) -> Result<i128, ContractError> {
-        let stream = load_stream(&env, stream_id)?;
+    /// - With a token allowlist in place, add `new_token` to it so creation accepts it
+    pub fn set_token(env: Env, new_token: Address) -> Result<(), ContractError> {
+        let mut config = get_config(&env);
+        config.admin.require_auth();
 
-        if stream.status == StreamStatus::Completed {
-            return Ok(stream.deposit_amount);
+        if get_active_stream_count(&env) > 0 {
+            return Err(ContractError::TokenInUse);
+        }
+        let probe = token::Client::new(&env, &new_token).try_decimals();
+        if !matches!(probe, Ok(Ok(_))) {
+            return Err(ContractError::InvalidToken);
         }
 
-        let now = if stream.status == StreamStatus::Cancelled {
-            stream
-                .cancelled_at
-                .expect("cancelled stream missing cancelled_at timestamp")
-        } else {
-            env.ledger().timestamp()
-        };
+        config.token = new_token.clone();
+        set_config(&env, &config);
 
-        Ok(accrual::calculate_accrued_amount(
-            stream.start_time,
-            stream.cliff_time,
-            stream.end_time,
-            stream.rate_per_second,
-            stream.deposit_amount,
-            now,
-        ))
+        env.events()
+            .publish((symbol_short!("config"), symbol_short!("token")), new_token);
+        Ok(())
     }
 
-    /// Retrieve the global contract configuration.
+    /// Set how long a sender must wait after cancelling before creating again.
     ///
-    /// Returns the contract's configuration containing the token address used for all
-    /// streams and the admin address authorized for administrative operations.
+    /// A sender who cancels with `cancel_stream`, `cancel_and_settle`, or a finalized
+    /// `initiate_cancel` cannot create streams until `cancel_cooldown_seconds` have
+    /// passed; creation fails with `ContractError::CancelCooldown`. Curbs
+    /// create/cancel loops that spam recipients and indexers. Admin cancellations
+    /// do not start the cooldown.
     ///
-    /// # Returns
-    /// - `Config`: Structure containing:
-    ///   - `token`: Address of the token contract used for all payment streams
-    ///   - `admin`: Address authorized to perform admin operations (pause, cancel, resume)
+    /// # Parameters
+    /// - `cancel_cooldown_seconds`: Cooldown length; `0` (the default) disables it
     ///
-    /// # Panics
-    /// - If the contract has not been initialized (missing config)
+    /// # Authorization
+    /// - Requires authorization from the contract admin
     ///
-    /// # Usage Notes
-    /// - This is a view function (read-only, no state changes)
-    /// - No authorization required (public information)
-    /// - Config is set once during `init()` and can be updated via `set_admin()`
-    /// - Useful for integrators to verify token and admin addresses
-    pub fn get_config(env: Env) -> Config {
-        get_config(&env)
+    /// # Events
+    /// - Publishes `(config, cancel_cd)` with the new cooldown
+    pub fn set_cancel_cooldown(env: Env, cancel_cooldown_seconds: u64) {
+        let mut config = get_config(&env);
+        config.admin.require_auth();
+
+        config.cancel_cooldown_seconds = cancel_cooldown_seconds;
+        set_config(&env, &config);
+
+        env.events().publish(
+            (symbol_short!("config"), symbol_short!("cancel_cd")),
+            cancel_cooldown_seconds,
+        );
     }
 
-    /// Update the admin address for the contract.
+    /// Restrict stream creation to the senders on the creator allowlist.
     ///
-    /// Allows the current admin to rotate the admin key by setting a new admin address.
-    /// This enables key rotation without redeploying the contract. Only the current admin
-    /// may call this function.
+    /// While enabled, every creation entrypoint (`create_stream` and its variants,
+    /// `create_streams`, `create_unfunded_stream`) fails with
+    /// `ContractError::CreatorNotAllowed` unless the sender was added with
+    /// `add_allowed_creator`. Turns the contract into an admin-gated factory without a
+    /// separate contract in front of it. Existing streams are unaffected.
     ///
     /// # Parameters
-    /// - `new_admin`: The new admin address that will replace the current admin
+    /// - `enabled`: Enforce the allowlist; `false` (the default) allows every sender
     ///
     /// # Authorization
-    /// - Requires authorization from the current admin address
-    ///
-    /// # Panics
-    /// - If the contract has not been initialized (missing config)
-    /// - If caller is not the current admin
-    ///
-    /// # State Changes
-    /// - Updates the admin address in the Config stored in instance storage
-    /// - Token address remains unchanged
+    /// - Requires authorization from the contract admin
     ///
     /// # Events
-    /// - Publishes `admin_updated(old_admin, new_admin)` event on success
-    ///
-    /// # Usage Notes
-    /// - This is a security-critical function for admin key rotation
-    /// - The new admin immediately gains all administrative privileges
-    /// - The old admin immediately loses all administrative privileges
-    /// - No restrictions on the new admin address (can be any valid address)
-    /// - Can be called multiple times to rotate keys as needed
-    ///
-    /// # Examples
-    /// - Rotate to a new admin key: `set_admin(env, new_admin_address)`
-    /// - Transfer admin to a multisig: `set_admin(env, multisig_address)`
-    pub fn set_admin(env: Env, new_admin: Address) {
+    /// - Publishes `(config, creators)` with the new setting
+    pub fn set_creators_allowlist(env: Env, enabled: bool) {
         let mut config = get_config(&env);
-        let old_admin = config.admin.clone();
-
-        // Only current admin can update admin
-        old_admin.require_auth();
+        config.admin.require_auth();
 
-        // Update admin in config
-        config.admin = new_admin.clone();
-        env.storage().instance().set(&DataKey::Config, &config);
+        config.creators_allowlist = enabled;
+        set_config(&env, &config);
 
-        // Emit event with old and new admin addresses
         env.events().publish(
-            (symbol_short!("admin"), symbol_short!("updated")),
-            (old_admin, new_admin),
+            (symbol_short!("config"), symbol_short!("creators")),
+            enabled,
         );
     }
 
-    /// Retrieve the complete state of a payment stream.
+    /// Mark cancelled streams `Settled` once they are fully drained.
     ///
-    /// Returns all stored information about a stream including participants, amounts,
-    /// timing parameters, and current status. This is a read-only view function.
+    /// While enabled, the withdrawal (or split withdrawal or sweep) that takes the last
+    /// of a `Cancelled` stream's accrued amount moves it to `StreamStatus::Settled`, a
+    /// terminal status meaning "cancelled and fully paid out". While disabled such
+    /// streams stay `Cancelled`. Streams that are already drained are not revisited.
     ///
     /// # Parameters
-    /// - `stream_id`: Unique identifier of the stream to query
-    ///
-    /// # Returns
-    /// - `Stream`: Complete stream state containing:
-    ///   - `stream_id`: Unique identifier
-    ///   - `sender`: Address that created and funded the stream
-    ///   - `recipient`: Address that receives the streamed tokens
-    ///   - `deposit_amount`: Total tokens deposited (initial funding)
-    ///   - `rate_per_second`: Streaming rate (tokens per second)
-    ///   - `start_time`: When streaming begins (ledger timestamp)
-    ///   - `cliff_time`: When tokens first become available (vesting cliff)
-    ///   - `end_time`: When streaming completes (ledger timestamp)
-    ///   - `withdrawn_amount`: Total tokens already withdrawn by recipient
-    ///   - `status`: Current stream status (Active, Paused, Completed, Cancelled)
+    /// - `enabled`: Settle drained cancelled streams; `false` (the default) leaves them
+    ///   `Cancelled`
     ///
-    /// # Panics
-    /// - If the stream does not exist (`stream_id` is invalid)
+    /// # Authorization
+    /// - Requires authorization from the contract admin
     ///
-    /// # Usage Notes
-    /// - This is a view function (read-only, no state changes)
-    /// - No authorization required (public information)
-    /// - Useful for UIs to display stream details
-    /// - Combine with `calculate_accrued()` to show real-time withdrawable amount
-    /// - Status indicates current operational state:
-    ///   - `Active`: Normal operation, recipient can withdraw
-    ///   - `Paused`: Temporarily halted, no withdrawals allowed
-    ///   - `Completed`: All tokens withdrawn, terminal state
-    ///   - `Cancelled`: Terminated early, unstreamed tokens refunded, terminal state
-    pub fn get_stream_state(env: Env, stream_id: u64) -> Result<Stream, ContractError> {
-        load_stream(&env, stream_id)
-    }
+    /// # Events
+    /// - Publishes `(config, settle)` with the new setting
+    pub fn set_settle_cancelled(env: Env, enabled: bool) {
+        let mut config = get_config(&env);
+        config.admin.require_auth();
 
-    /// Internal helper to check authorization for sender or admin.
-    fn require_sender_or_admin(_env: &Env, sender: &Address) {
-        // Only the sender can manage their own stream via these paths.
-        // Admin overrides are handled by the 'as_admin' specific functions.
-        sender.require_auth();
-    }
+        config.settle_cancelled = enabled;
+        set_config(&env, &config);
 
-    fn require_cancellable_status(env: &Env, status: StreamStatus) {
-        if status != StreamStatus::Active && status != StreamStatus::Paused {
-            panic_with_error!(env, ContractError::InvalidState);
-        }
+        env.events()
+            .publish((symbol_short!("config"), symbol_short!("settle")), enabled);
     }
-}
 
-#[contractimpl]
-impl FluxoraStream {
-    /// Cancel a payment stream as the contract admin.
+    /// Require the recipient to accept cancellation payouts pushed by `cancel_full`.
     ///
-    /// Administrative override to cancel any stream, bypassing sender authorization.
-    /// Identical behavior to `cancel_stream` but requires admin authorization instead
-    /// of sender authorization. Useful for emergency interventions or dispute resolution.
+    /// While enabled, `cancel_full` with `pay_recipient` holds the recipient's accrued
+    /// balance in escrow instead of transferring it, and the recipient collects it with
+    /// `claim_cancelled`. `cancel_and_settle` is unaffected, since the recipient already
+    /// co-signs it. Payouts escrowed earlier stay claimable after disabling.
     ///
     /// # Parameters
-    /// - `stream_id`: Unique identifier of the stream to cancel
+    /// - `enabled`: Escrow cancellation payouts; `false` (the default) pays them directly
     ///
     /// # Authorization
-    /// - Requires authorization from the contract admin (set during `init`)
+    /// - Requires authorization from the contract admin
     ///
-    /// # Behavior
-    /// Same as `cancel_stream`:
-    /// 1. Validates stream is in `Active` or `Paused` state
-    /// 2. Calculates accrued amount based on time elapsed
-    /// 3. Refunds unstreamed tokens to sender
-    /// 4. Sets stream status to `Cancelled`
-    /// 5. Accrued amount remains for recipient to withdraw
+    /// # Events
+    /// - Publishes `(config, pay_ack)` with the new setting
+    pub fn set_cancel_payout_ack(env: Env, enabled: bool) {
+        let mut config = get_config(&env);
+        config.admin.require_auth();
+
+        config.cancel_payout_ack = enabled;
+        set_config(&env, &config);
+
+        env.events()
+            .publish((symbol_short!("config"), symbol_short!("pay_ack")), enabled);
+    }
+
+    /// Freeze new deposits while leaving existing streams fully operable.
     ///
-    /// # Panics
-    /// - If stream is not `Active` or `Paused`
-    /// - If the stream does not exist
-    /// - If caller is not the admin
-    /// - If token transfer fails
+    /// While paused, every entrypoint that pulls stream tokens in (`create_stream` and
+    /// its variants, `create_streams`, `fund_stream`, `extend_stream`) fails with
+    /// `ContractError::DepositsPaused`. Withdrawals, cancellations, and the other
+    /// stream operations are unaffected, so recipients keep being paid.
     ///
-    /// # Events
-    /// - Publishes `Cancelled(stream_id)` event on success
+    /// # Parameters
+    /// - `paused`: Reject new deposits; `false` (the default) accepts them
     ///
-    /// # Usage Notes
-    /// - Admin can cancel any stream regardless of sender
-    /// - Use for emergency situations or dispute resolution
-    /// - Sender still receives refund of unstreamed tokens
-    /// - Recipient can still withdraw accrued amount
-    pub fn cancel_stream_as_admin(env: Env, stream_id: u64) -> Result<(), ContractError> {
-        let admin = get_admin(&env);
-        admin.require_auth();
+    /// # Authorization
+    /// - Requires authorization from the contract admin
+    ///
+    /// # Events
+    /// - Publishes `(config, deposits)` with the new setting
+    pub fn set_deposits_paused(env: Env, paused: bool) {
+        let mut config = get_config(&env);
+        config.admin.require_auth();
 
-        let mut stream = load_stream(&env, stream_id)?;
+        config.deposits_paused = paused;
+        set_config(&env, &config);
 
-        assert!(
-            stream.status == StreamStatus::Active || stream.status == StreamStatus::Paused,
-            "stream must be active or paused to cancel"
-        );
+        env.events()
+            .publish((symbol_short!("config"), symbol_short!("deposits")), paused);
+    }
 
-        let accrued = Self::calculate_accrued(env.clone(), stream_id)?;
-        let unstreamed = stream.deposit_amount - accrued;
+    /// Recompute the active-stream and per-status counters from stored stream statuses.
+    ///
+    /// Maintenance path for when the counters have drifted (a bug or a migration). The
+    /// rebuild is paged so the full id space can be covered within resource limits:
+    /// a call with `start_id == 0` resets the counters, and every call adds the
+    /// streams in `[start_id, start_id + limit)`. Keep calling with the returned id
+    /// until it equals the stream count.
+    ///
+    /// # Parameters
+    /// - `start_id`: First stream id of this page; `0` starts a fresh rebuild
+    /// - `limit`: Maximum number of stream ids to scan in this call
+    ///
+    /// # Returns
+    /// - `u64`: The `start_id` for the next page (the stream count once finished)
+    ///
+    /// # Authorization
+    /// - Requires authorization from the contract admin
+    ///
+    /// # Usage Notes
+    /// - Pages must be run in order without interleaved stream creation or
+    ///   status changes, otherwise the rebuilt values can be off by those changes
+    pub fn rebuild_counters(env: Env, start_id: u64, limit: u64) -> u64 {
+        get_admin(&env).require_auth();
 
-        // CEI: update state before external token transfer to reduce reentrancy risk.
-        stream.status = StreamStatus::Cancelled;
-        save_stream(&env, &stream);
+        const STATUSES: [StreamStatus; 7] = [
+            StreamStatus::Active,
+            StreamStatus::Paused,
+            StreamStatus::Completed,
+            StreamStatus::Cancelled,
+            StreamStatus::PendingAcceptance,
+            StreamStatus::Unfunded,
+            StreamStatus::Settled,
+        ];
 
-        if unstreamed > 0 {
-            let token_client = token::Client::new(&env, &get_token(&env));
-            token_client.transfer(&env.current_contract_address(), &stream.sender, &unstreamed);
+        let end_id = start_id.saturating_add(limit).min(get_stream_count(&env));
+        let mut active = 0;
+        let mut by_status = [0u64; STATUSES.len()];
+        if start_id != 0 {
+            active = get_active_stream_count(&env);
+            for (count, status) in by_status.iter_mut().zip(STATUSES) {
+                *count = get_status_count(&env, status);
+            }
         }
 
-        env.events().publish(
-            (symbol_short!("cancelled"), stream_id),
-            StreamEvent::Cancelled(stream_id),
-        );
-        Ok(())
+        for stream_id in start_id..end_id {
+            if let Ok(stream) = load_stream(&env, stream_id) {
+                if !matches!(
+                    stream.status,
+                    StreamStatus::Completed | StreamStatus::Cancelled | StreamStatus::Settled
+                ) {
+                    active += 1;
+                }
+                by_status[stream.status as usize] += 1;
+            }
+        }
+
+        set_active_stream_count(&env, active);
+        for (count, status) in by_status.into_iter().zip(STATUSES) {
+            set_status_count(&env, status, count);
+        }
+        end_id.max(start_id)
     }
 
     /// Pause a payment stream as the contract admin.
@@ -845,23 +6145,54 @@ impl FluxoraStream {
         let admin = get_admin(&env);
         admin.require_auth();
 
-        let mut stream = load_stream(&env, stream_id)?;
+        let stream = load_stream(&env, stream_id)?;
 
         assert!(
             stream.status == StreamStatus::Active,
             "stream is not active"
         );
 
-        stream.status = StreamStatus::Paused;
-        save_stream(&env, &stream);
-
-        env.events().publish(
-            (symbol_short!("paused"), stream_id),
-            StreamEvent::Paused(stream_id),
-        );
+        Self::pause_active_stream(&env, stream);
         Ok(())
     }
 
+    /// Pause every `Active` stream of `sender` as the contract admin.
+    ///
+    /// Emergency freeze for a compromised sender: each `Active` stream is paused as
+    /// `pause_stream_as_admin` would; streams in any other status are skipped. Only the
+    /// sender's first `MAX_STREAMS_PAGE` streams (in creation order) are read, keeping
+    /// the call within ledger read limits; pause any later ones with
+    /// `pause_stream_as_admin`.
+    ///
+    /// # Parameters
+    /// - `sender`: Address whose streams to pause
+    ///
+    /// # Returns
+    /// - `u32`: Number of streams paused
+    ///
+    /// # Authorization
+    /// - Requires authorization from the contract admin
+    ///
+    /// # Events
+    /// - Publishes `Paused(stream_id)` for each stream paused
+    pub fn pause_streams_by_sender_as_admin(env: Env, sender: Address) -> u32 {
+        get_admin(&env).require_auth();
+
+        let mut paused = 0;
+        for stream_id in get_sender_streams(&env, &sender)
+            .iter()
+            .take(MAX_STREAMS_PAGE as usize)
+        {
+            if let Ok(stream) = load_stream(&env, stream_id) {
+                if stream.status == StreamStatus::Active {
+                    Self::pause_active_stream(&env, stream);
+                    paused += 1;
+                }
+            }
+        }
+        paused
+    }
+
     /// Resume a paused payment stream as the contract admin.
     ///
     /// Administrative override to resume any paused stream, bypassing sender authorization.
@@ -895,6 +6226,7 @@ impl FluxoraStream {
             "stream is not paused"
         );
 
+        Self::checkpoint_accrual(&env, &mut stream);
         stream.status = StreamStatus::Active;
         save_stream(&env, &stream);
 
@@ -1,6 +1,6 @@
 extern crate std;
 
-use fluxora_stream::{FluxoraStream, FluxoraStreamClient, StreamStatus};
+use fluxora_stream::{ContractError, FluxoraStream, FluxoraStreamClient, StreamStatus};
 use soroban_sdk::{
     log,
     testutils::{Address as _, Ledger},
@@ -206,13 +206,13 @@ fn withdraw_accrued_amount_updates_balances_and_state() {
 }
 
 #[test]
-#[should_panic(expected = "nothing to withdraw")]
-fn withdraw_before_cliff_panics() {
+fn withdraw_before_cliff_returns_zero() {
     let ctx = TestContext::setup();
     let stream_id = ctx.create_stream_with_cliff(500);
 
     ctx.env.ledger().set_timestamp(100);
-    ctx.client().withdraw(&stream_id);
+    assert_eq!(ctx.client().withdraw(&stream_id), 0);
+    assert_eq!(ctx.token.balance(&ctx.recipient), 0);
 }
 
 #[test]
@@ -1026,20 +1026,10 @@ fn integration_pause_resume_withdraw_lifecycle() {
     );
 
     // Attempt to withdraw while paused — should fail
-    let withdrawal_result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
-        ctx.client().withdraw(&stream_id);
-    }));
-    let err = withdrawal_result.expect_err("withdrawal should panic while stream is paused");
-    // Ensure the panic reason matches the expected paused-stream invariant
-    let panic_msg = err
-        .downcast_ref::<&str>()
-        .copied()
-        .or_else(|| err.downcast_ref::<String>().map(|s| s.as_str()))
-        .unwrap_or("<non-string panic payload>");
-    assert!(
-        panic_msg.contains("cannot withdraw from paused stream"),
-        "unexpected panic message when withdrawing from paused stream: {}",
-        panic_msg
+    assert_eq!(
+        ctx.client().try_withdraw(&stream_id),
+        Err(Ok(ContractError::StreamPaused)),
+        "withdrawal should fail while stream is paused"
     );
 
     // Verify stream still paused and no tokens transferred
@@ -1346,10 +1336,10 @@ fn test_create_many_streams_from_same_sender() {
 
     let cpu_insns = ctx.env.budget().cpu_instruction_cost();
     log!(&ctx.env, "cpu_insns", cpu_insns);
-    assert!(cpu_insns == 19_631_671);
+    assert!(cpu_insns == 42_358_975);
 
     // Check memory bytes consumed
     let mem_bytes = ctx.env.budget().memory_bytes_cost();
     log!(&ctx.env, "mem_bytes", mem_bytes);
-    assert!(mem_bytes == 4_090_035);
+    assert!(mem_bytes == 8_734_698);
 }
@@ -0,0 +1,52 @@
+//! Replacement contract used by the `fluxora_stream` upgrade test.
+//!
+//! It only reads existing stream entries back out of persistent storage, so the
+//! test can prove that data written by the original code survives an upgrade.
+//! The stored value is returned untouched, which keeps the fixture valid as the
+//! `Stream` layout evolves.
+//!
+//! Rebuild the committed wasm (the test VM only accepts MVP wasm features):
+//!
+//! ```bash
+//! RUSTFLAGS="-C target-cpu=mvp" cargo +nightly build -Zbuild-std=core,alloc \
+//!     --target wasm32-unknown-unknown --release -p fluxora_upgrade_fixture
+//! cp target/wasm32-unknown-unknown/release/fluxora_upgrade_fixture.wasm \
+//!     contracts/stream/testdata/
+//! ```
+//!
+//! On stable, the prebuilt `core` needs its relocations compressed instead:
+//!
+//! ```bash
+//! RUSTFLAGS="-C target-cpu=mvp -C link-arg=--compress-relocations -C link-arg=--strip-debug" \
+//!     cargo build --target wasm32-unknown-unknown --release -p fluxora_upgrade_fixture
+//! ```
+#![no_std]
+
+use soroban_sdk::{contract, contractimpl, contracttype, Env, Val};
+
+/// Mirrors the stream variants of `fluxora_stream::DataKey`.
+#[contracttype]
+pub enum DataKey {
+    Stream(u64),
+    StreamV2(u64),
+}
+
+#[contract]
+pub struct UpgradeFixture;
+
+#[contractimpl]
+impl UpgradeFixture {
+    /// Marker proving the upgraded code is the one executing.
+    pub fn version(_env: Env) -> u32 {
+        2
+    }
+
+    /// Return the raw stored stream entry, packed or in the original layout.
+    pub fn get_stream_state(env: Env, stream_id: u64) -> Val {
+        let storage = env.storage().persistent();
+        storage
+            .get(&DataKey::StreamV2(stream_id))
+            .or_else(|| storage.get(&DataKey::Stream(stream_id)))
+            .expect("stream not found")
+    }
+}